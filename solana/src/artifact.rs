@@ -1,14 +1,31 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
-use eyre::{Result, bail};
+use clap::{Args, Parser, Subcommand};
+use eyre::{Result, bail, eyre};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
 
+use crate::config::Config;
 use crate::types::{AxelarNetwork, Programs};
+use crate::utils::{
+    ARTIFACT_HASH_KEY, ARTIFACT_VERSION_KEY, CHAINS_KEY, CONTRACTS_KEY, read_json_file_from_path,
+    write_json_to_file_path,
+};
 
 const AXELAR_R2_BASE_URL: &str = "https://static.axelar.network";
 const GITHUB_RELEASES_BASE_URL: &str =
     "https://github.com/axelarnetwork/axelar-amplifier-solana/releases/download";
 
+/// Name of the checksum manifest published next to the artifacts of a release, in the
+/// `sha256sum` format.
+const CHECKSUM_MANIFEST_FILENAME: &str = "checksums.txt";
+
+const ARTIFACTS_DIR: &str = "./artifacts";
+const ARTIFACTS_INDEX_FILENAME: &str = "index.json";
+
 /// Get the download URL for a program artifact
 /// - Semver (e.g., 0.1.7) → GitHub releases
 /// - Commit hash (e.g., 12e6126) → R2
@@ -39,14 +56,241 @@ pub(crate) fn get_artifact_url(
     }
 }
 
-/// Download a program artifact from GitHub releases or R2
+/// Where to take the program binary from.
+#[derive(Args, Debug, Clone)]
+pub(crate) struct ArtifactArgs {
+    /// Path to the program bytecode (.so file)
+    #[clap(long, conflicts_with_all = &["version", "artifact-dir"])]
+    pub(crate) program_path: Option<String>,
+
+    /// Version to download: semver (e.g., 0.1.7) from GitHub, or commit hash (e.g., 12e6126) from R2
+    #[clap(long, conflicts_with_all = &["program-path", "artifact-dir"])]
+    pub(crate) version: Option<String>,
+
+    /// Directory containing local builds (e.g., ./target/deploy)
+    #[clap(long, conflicts_with_all = &["program-path", "version"])]
+    pub(crate) artifact_dir: Option<PathBuf>,
+
+    /// Never download artifacts, only use the ones already in the local cache
+    #[clap(long)]
+    pub(crate) offline: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Commands {
+    /// List the program artifacts in the local cache
+    List,
+
+    /// Re-hash the cached artifacts and report the ones that do not match their recorded hash
+    Verify,
+
+    /// Remove cached artifacts that are not deployed according to the chains info file
+    Prune(PruneArgs),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct PruneArgs {
+    /// Remove every cached artifact, including the deployed ones
+    #[clap(long)]
+    all: bool,
+
+    /// Only print what would be removed
+    #[clap(long)]
+    dry_run: bool,
+}
+
+/// Entry of the local artifact cache. The binary itself is stored under its SHA-256 hash.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CachedArtifact {
+    program: String,
+    version: String,
+    network: String,
+    sha256: String,
+    url: String,
+}
+
+/// Index of the local artifact cache, keyed by program, network and version.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ArtifactIndex(BTreeMap<String, CachedArtifact>);
+
+impl ArtifactIndex {
+    fn load() -> Result<Self> {
+        let path = artifacts_dir().join(ARTIFACTS_INDEX_FILENAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        read_json_file_from_path(&path)
+    }
+
+    fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(artifacts_dir())?;
+        write_json_to_file_path(self, &artifacts_dir().join(ARTIFACTS_INDEX_FILENAME))
+    }
+}
+
+fn artifacts_dir() -> PathBuf {
+    PathBuf::from(ARTIFACTS_DIR)
+}
+
+/// Path of a cached binary, addressed by its SHA-256 hash.
+fn blob_path(sha256: &str) -> PathBuf {
+    artifacts_dir().join("sha256").join(format!("{sha256}.so"))
+}
+
+fn cache_key(program: &Programs, version: &str, network: AxelarNetwork) -> String {
+    format!(
+        "{}-{}-{version}",
+        program_to_so_filename(program),
+        network.as_str()
+    )
+}
+
+/// Hex-encoded SHA-256 hash of a program binary.
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// URL of the checksum manifest published alongside the artifact at `artifact_url`.
+fn checksum_manifest_url(artifact_url: &str) -> Result<String> {
+    let (base, _) = artifact_url
+        .rsplit_once('/')
+        .ok_or_else(|| eyre!("Invalid artifact URL: {artifact_url}"))?;
+    Ok(format!("{base}/{CHECKSUM_MANIFEST_FILENAME}"))
+}
+
+/// Find the hash of `filename` in a `sha256sum`-style manifest (`<hash>  <filename>` per line,
+/// with an optional `*` marking binary mode).
+fn find_in_checksum_manifest(manifest: &str, filename: &str) -> Option<String> {
+    manifest.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim_start().trim_start_matches('*');
+        (name == filename && hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| hash.to_lowercase())
+    })
+}
+
+/// Hash pinned for `version` in the chains info file: the artifact hash recorded when that
+/// version was deployed.
+fn pinned_hash(config: &Config, program: &Programs, version: &str) -> Option<String> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file).ok()?;
+    let entry = env.pointer(&format!(
+        "/{CHAINS_KEY}/{}/{CONTRACTS_KEY}/{}",
+        config.chain,
+        crate::deploy::program_key_from_program(program)
+    ))?;
+
+    recorded_artifact_hash(entry, version)
+}
+
+/// The artifact hash of a contract entry, if it was recorded from the `version` artifact. Hashes
+/// of local builds are recorded without an artifact version and never pin a download.
+fn recorded_artifact_hash(entry: &Value, version: &str) -> Option<String> {
+    let recorded_version = entry.get(ARTIFACT_VERSION_KEY)?.as_str()?;
+    if normalize_version(recorded_version) != normalize_version(version) {
+        return None;
+    }
+
+    entry
+        .get(ARTIFACT_HASH_KEY)?
+        .as_str()
+        .map(str::to_lowercase)
+}
+
+fn normalize_version(version: &str) -> String {
+    if is_commit_hash(version) {
+        version.to_lowercase().chars().take(7).collect()
+    } else {
+        version.to_owned()
+    }
+}
+
+/// The hash of the artifact at `url` listed in the checksum manifest published alongside it.
+async fn manifest_hash(client: &reqwest::Client, url: &str) -> Result<String> {
+    let filename = url.rsplit('/').next().unwrap_or_default();
+    let manifest_url = checksum_manifest_url(url)?;
+    let response = client.get(&manifest_url).send().await?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to download the checksum manifest {manifest_url}: {}",
+            response.status()
+        );
+    }
+    let manifest = response.text().await?;
+    let hash = find_in_checksum_manifest(&manifest, filename)
+        .ok_or_else(|| eyre!("{filename} is not listed in {manifest_url}"))?;
+    println!("Expecting SHA-256 {hash} (from {manifest_url})");
+
+    Ok(hash)
+}
+
+/// Download a program artifact from GitHub releases or R2, verifying its SHA-256 hash against
+/// the pinned hash or the release checksum manifest. The manifest is required for GitHub
+/// releases only: commit builds on R2 without one are used unverified, with a warning. Downloaded
+/// artifacts are kept in a local content-addressed cache and reused on later runs.
 pub(crate) async fn download_artifact(
     program: &Programs,
     version: &str,
     network: AxelarNetwork,
+    pinned_sha256: Option<&str>,
+    offline: bool,
 ) -> Result<PathBuf> {
     let url = get_artifact_url(program, version, network)?;
-    let source = if is_semver(version) { "GitHub" } else { "R2" };
+    let key = cache_key(program, &normalize_version(version), network);
+    let mut index = ArtifactIndex::load()?;
+
+    if let Some(cached) = index.0.get(&key) {
+        let path = blob_path(&cached.sha256);
+        let actual = std::fs::read(&path).ok().map(|data| sha256_hex(&data));
+
+        if actual.as_deref() == Some(cached.sha256.as_str()) {
+            if let Some(pinned) = pinned_sha256 {
+                if pinned != cached.sha256 {
+                    bail!(
+                        "Cached artifact {key} has hash {}, but the chains info file pins {pinned}",
+                        cached.sha256
+                    );
+                }
+            }
+            println!("Using cached artifact {key} ({})", path.display());
+            return Ok(path);
+        }
+
+        eprintln!("WARNING: Cached artifact {key} is missing or corrupted, ignoring it");
+    }
+
+    if offline {
+        bail!(
+            "Artifact {key} is not in the local cache and network access is disabled (--offline)"
+        );
+    }
+
+    // Cloudflare R2 (sometimes) returns 403 for requests without a User-Agent
+    // header, which is the case with reqwest's default client.
+    let client = reqwest::Client::builder()
+        .user_agent("solana-axelar-cli")
+        .build()?;
+
+    let from_github = is_semver(version);
+    let expected = match pinned_sha256 {
+        Some(pinned) => {
+            println!("Expecting SHA-256 {pinned} (pinned in chains info)");
+            Some(pinned.to_owned())
+        }
+        None => match manifest_hash(&client, &url).await {
+            Ok(hash) => Some(hash),
+            // Builds of a commit on R2 are not published with a checksum manifest.
+            Err(err) if !from_github => {
+                eprintln!("WARNING: {err}. The artifact can't be verified before use.");
+                None
+            }
+            Err(err) => bail!(
+                "{err}. Refusing to use an unverified artifact, use --program-path with a binary \
+                you verified instead."
+            ),
+        },
+    };
+
+    let source = if from_github { "GitHub" } else { "R2" };
     println!(
         "Downloading {} from {} ({})",
         program_to_so_filename(program),
@@ -54,35 +298,40 @@ pub(crate) async fn download_artifact(
         url
     );
 
-    // Cloudflare R2 (sometimes) returns 403 for requests without a User-Agent
-    // header, which is the case with reqwest's default client.
-    let client = reqwest::Client::builder()
-        .user_agent("solana-axelar-cli")
-        .build()?;
     let response = client.get(&url).send().await?;
     if !response.status().is_success() {
         bail!("Failed to download from {}: {}", url, response.status());
     }
 
     let bytes = response.bytes().await?;
+    let actual = sha256_hex(&bytes);
+    match &expected {
+        Some(expected) if *expected != actual => {
+            bail!("Checksum mismatch for {url}: expected {expected}, got {actual}");
+        }
+        Some(_) => {}
+        None => println!("SHA-256 of the unverified artifact: {actual}"),
+    }
 
-    let artifacts_dir = PathBuf::from("./artifacts");
-    std::fs::create_dir_all(&artifacts_dir)?;
+    let path = blob_path(&actual);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, &bytes)?;
 
-    let normalized_version = if is_commit_hash(version) {
-        version.to_lowercase().chars().take(7).collect()
-    } else {
-        version.to_owned()
-    };
-    let filename = format!(
-        "{}-{}.so",
-        program_to_so_filename(program),
-        normalized_version
+    index.0.insert(
+        key,
+        CachedArtifact {
+            program: program_to_so_filename(program).to_owned(),
+            version: normalize_version(version),
+            network: network.as_str().to_owned(),
+            sha256: actual,
+            url,
+        },
     );
-    let path = artifacts_dir.join(&filename);
-    std::fs::write(&path, &bytes)?;
+    index.save()?;
 
-    println!("Downloaded to {}", path.display());
+    println!("Downloaded and verified {}", path.display());
     Ok(path)
 }
 
@@ -99,14 +348,26 @@ pub(crate) fn resolve_from_artifact_dir(program: &Programs, dir: &Path) -> Resul
 /// Resolve program path from one of three sources
 pub(crate) async fn resolve_program_path(
     program: &Programs,
-    program_path: Option<&str>,
-    version: Option<&str>,
-    artifact_dir: Option<&Path>,
-    network: AxelarNetwork,
+    args: &ArtifactArgs,
+    config: &Config,
 ) -> Result<PathBuf> {
-    match (program_path, version, artifact_dir) {
+    match (
+        args.program_path.as_deref(),
+        args.version.as_deref(),
+        args.artifact_dir.as_deref(),
+    ) {
         (Some(path), None, None) => Ok(PathBuf::from(path)),
-        (None, Some(ver), None) => download_artifact(program, ver, network).await,
+        (None, Some(ver), None) => {
+            let pinned = pinned_hash(config, program, ver);
+            download_artifact(
+                program,
+                ver,
+                config.axelar_network,
+                pinned.as_deref(),
+                args.offline,
+            )
+            .await
+        }
         (None, None, Some(dir)) => resolve_from_artifact_dir(program, dir),
         (None, None, None) => {
             bail!("One of --program-path, --version, or --artifact-dir is required")
@@ -115,6 +376,147 @@ pub(crate) async fn resolve_program_path(
     }
 }
 
+pub(crate) fn handle_command(command: Commands, config: &Config) -> Result<()> {
+    match command {
+        Commands::List => list(),
+        Commands::Verify => verify(),
+        Commands::Prune(args) => prune(&args, config),
+    }
+}
+
+fn list() -> Result<()> {
+    let index = ArtifactIndex::load()?;
+    if index.0.is_empty() {
+        println!("No cached artifacts in {ARTIFACTS_DIR}");
+        return Ok(());
+    }
+
+    for (key, cached) in &index.0 {
+        let size = std::fs::metadata(blob_path(&cached.sha256))
+            .map_or_else(|_| "missing".to_owned(), |m| format!("{} bytes", m.len()));
+        println!("{key}");
+        println!("   SHA-256: {}", cached.sha256);
+        println!("   Size: {size}");
+        println!("   Source: {}", cached.url);
+    }
+
+    Ok(())
+}
+
+fn verify() -> Result<()> {
+    let index = ArtifactIndex::load()?;
+    let mut failures = 0_usize;
+
+    for (key, cached) in &index.0 {
+        match std::fs::read(blob_path(&cached.sha256)) {
+            Ok(data) if sha256_hex(&data) == cached.sha256 => println!("\u{2705} {key}"),
+            Ok(data) => {
+                failures = failures.saturating_add(1);
+                println!(
+                    "\u{274c} {key}: expected {}, got {}",
+                    cached.sha256,
+                    sha256_hex(&data)
+                );
+            }
+            Err(err) => {
+                failures = failures.saturating_add(1);
+                println!("\u{274c} {key}: {err}");
+            }
+        }
+    }
+
+    if failures > 0 {
+        bail!("{failures} cached artifact(s) failed verification");
+    }
+
+    println!("All {} cached artifacts verified.", index.0.len());
+    Ok(())
+}
+
+/// Artifact hashes recorded for any contract of any chain in the chains info file.
+fn deployed_hashes(config: &Config) -> Result<BTreeSet<String>> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let hashes = env
+        .get(CHAINS_KEY)
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|chains| chains.values())
+        .filter_map(|chain| chain.get(CONTRACTS_KEY)?.as_object())
+        .flat_map(|contracts| contracts.values())
+        .filter_map(|contract| contract.get(ARTIFACT_HASH_KEY)?.as_str())
+        .map(str::to_lowercase)
+        .collect();
+
+    Ok(hashes)
+}
+
+fn prune(args: &PruneArgs, config: &Config) -> Result<()> {
+    let keep = if args.all {
+        BTreeSet::new()
+    } else {
+        deployed_hashes(config)?
+    };
+
+    let mut index = ArtifactIndex::load()?;
+    let removed: Vec<String> = index
+        .0
+        .iter()
+        .filter(|(_, cached)| !keep.contains(&cached.sha256))
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in &removed {
+        println!("Removing {key}");
+        if !args.dry_run {
+            index.0.remove(key);
+        }
+    }
+
+    // Remove binaries no index entry points at anymore, including leftovers of failed runs
+    let referenced: BTreeSet<&str> = index
+        .0
+        .iter()
+        .filter(|(key, _)| !removed.contains(key))
+        .map(|(_, cached)| cached.sha256.as_str())
+        .collect();
+    let blobs_dir = artifacts_dir().join("sha256");
+    let mut orphans = Vec::new();
+    if blobs_dir.exists() {
+        for entry in std::fs::read_dir(&blobs_dir)? {
+            let path = entry?.path();
+            let hash = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            if !referenced.contains(hash) {
+                orphans.push(path);
+            }
+        }
+    }
+    for path in &orphans {
+        println!("Deleting {}", path.display());
+        if !args.dry_run {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    if args.dry_run {
+        println!(
+            "Dry run: {} cache entries and {} files would be removed.",
+            removed.len(),
+            orphans.len()
+        );
+    } else {
+        index.save()?;
+        println!(
+            "Removed {} cache entries and {} files.",
+            removed.len(),
+            orphans.len()
+        );
+    }
+
+    Ok(())
+}
+
 /// Check if string is a semantic version (e.g., 0.1.7)
 fn is_semver(s: &str) -> bool {
     Regex::new(r"^\d+\.\d+\.\d+$")
@@ -137,11 +539,7 @@ fn program_to_package_name(program: &Programs) -> Result<&'static str> {
         Programs::Its => Ok("solana-axelar-its"),
         Programs::Operators => Ok("solana-axelar-operators"),
         Programs::Memo => Ok("solana-axelar-memo"),
-        Programs::Multicall => {
-            bail!(
-                "Multicall not available for download. Use --program-path or --artifact-dir instead."
-            )
-        }
+        Programs::Multicall => Ok("solana-axelar-multicall"),
     }
 }
 
@@ -265,9 +663,96 @@ mod tests {
     }
 
     #[test]
-    fn test_multicall_not_available() {
+    fn test_multicall_urls() {
         let network = AxelarNetwork::DevnetAmplifier;
-        assert!(get_artifact_url(&Programs::Multicall, "0.1.7", network).is_err());
-        assert!(get_artifact_url(&Programs::Multicall, "12e6126", network).is_err());
+        assert_eq!(
+            get_artifact_url(&Programs::Multicall, "0.1.7", network).unwrap(),
+            format!(
+                "{GITHUB_RELEASES_BASE_URL}/solana-axelar-multicall-v0.1.7/solana_axelar_multicall-devnet-amplifier.so"
+            )
+        );
+        assert_eq!(
+            get_artifact_url(&Programs::Multicall, "12e6126", network).unwrap(),
+            format!(
+                "{AXELAR_R2_BASE_URL}/releases/solana/solana-axelar-multicall/12e6126/devnet-amplifier/programs/solana_axelar_multicall.so"
+            )
+        );
+    }
+
+    #[test]
+    fn test_checksum_manifest_url() {
+        let url = checksum_manifest_url(
+            "https://github.com/axelarnetwork/axelar-amplifier-solana/releases/download/solana-axelar-gateway-v0.1.7/solana_axelar_gateway-devnet-amplifier.so",
+        )
+        .unwrap();
+        assert_eq!(
+            url,
+            "https://github.com/axelarnetwork/axelar-amplifier-solana/releases/download/solana-axelar-gateway-v0.1.7/checksums.txt"
+        );
+    }
+
+    #[test]
+    fn test_find_in_checksum_manifest() {
+        let gateway_hash = "a".repeat(64);
+        let its_hash = "B".repeat(64);
+        let manifest = format!(
+            "{gateway_hash}  solana_axelar_gateway-devnet-amplifier.so\n{its_hash} *solana_axelar_its-devnet-amplifier.so\n"
+        );
+
+        assert_eq!(
+            find_in_checksum_manifest(&manifest, "solana_axelar_gateway-devnet-amplifier.so"),
+            Some(gateway_hash)
+        );
+        assert_eq!(
+            find_in_checksum_manifest(&manifest, "solana_axelar_its-devnet-amplifier.so"),
+            Some("b".repeat(64))
+        );
+        assert_eq!(
+            find_in_checksum_manifest(&manifest, "solana_axelar_memo-devnet-amplifier.so"),
+            None
+        );
+        assert_eq!(
+            find_in_checksum_manifest("abc  solana_axelar_gateway.so", "solana_axelar_gateway.so"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_recorded_artifact_hash() {
+        let hash = "ab".repeat(32);
+        let versioned = serde_json::json!({
+            "version": "0.1.7",
+            "artifactVersion": "0.1.7",
+            "artifactHash": hash.to_uppercase(),
+        });
+        assert_eq!(
+            recorded_artifact_hash(&versioned, "0.1.7"),
+            Some(hash.clone())
+        );
+        assert_eq!(recorded_artifact_hash(&versioned, "0.1.8"), None);
+
+        let commit = serde_json::json!({
+            "artifactVersion": "12e6126",
+            "artifactHash": hash,
+        });
+        assert_eq!(
+            recorded_artifact_hash(&commit, "12E6126ABCDEF"),
+            Some(hash.clone())
+        );
+
+        // Upgraded from a local build: the version is stale and the hash is not the artifact's.
+        let local_build = serde_json::json!({
+            "version": "0.1.7",
+            "artifactHash": hash,
+        });
+        assert_eq!(recorded_artifact_hash(&local_build, "0.1.7"), None);
     }
 }
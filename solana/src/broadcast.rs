@@ -14,6 +14,7 @@ use solana_sdk::transaction::Transaction;
 use crate::config::Config;
 use crate::types::SignedSolanaTransaction;
 use crate::utils::{self, print_transaction_result};
+use crate::{gateway, program};
 
 #[derive(Debug, Clone)]
 pub(crate) struct BroadcastArgs {
@@ -166,7 +167,25 @@ pub(crate) fn broadcast_solana_transaction(
     );
 
     match submit_solana_transaction(&config.url, &signed_tx_data) {
-        Ok(signature) => print_transaction_result(config, Ok(signature)),
+        Ok(signature) => {
+            print_transaction_result(config, Ok(signature))?;
+            let (transaction, _) = construct_transaction(&signed_tx_data)?;
+            if gateway::is_call_contract(&transaction) {
+                let rpc_client = RpcClient::new_with_commitment(
+                    config.url.clone(),
+                    CommitmentConfig::confirmed(),
+                );
+                // The transaction is sent: a failed message lookup must not fail the command.
+                if let Err(err) =
+                    gateway::print_call_contract_messages(&rpc_client, config, &signature)
+                {
+                    eprintln!(
+                        "\u{26A0}\u{FE0F}  Warning: failed to fetch the sent messages: {err}"
+                    );
+                }
+            }
+            program::apply_pending_update(config, &signed_tx_data.unsigned_tx_data.params)
+        }
         Err(err) => print_transaction_result(config, Err(err)),
     }
 }
@@ -1,108 +1,142 @@
 use std::io::{self, Write};
-use std::path::{Path, PathBuf};
-use std::process::Command;
 
-use clap::Args;
-use eyre::Result;
+use clap::{Args, ValueEnum};
+use eyre::{Result, eyre};
 use serde_json::Value;
+use solana_clap_v3_utils::keypair::signer_from_path;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::{Signer, read_keypair_file};
+use solana_sdk::signature::Signer;
 
-use crate::artifact;
+use crate::artifact::{self, ArtifactArgs};
+use crate::program_loader::{self, BufferSource, LoaderAccount};
 use crate::types::Programs;
 use crate::utils::{
     ADDRESS_KEY, CHAINS_KEY, CONTRACTS_KEY, GAS_SERVICE_KEY, GATEWAY_KEY, GOVERNANCE_KEY, ITS_KEY,
     MEMO_KEY, MULTICALL_KEY, OPERATORS_KEY, UPGRADE_AUTHORITY_KEY, VERSION_KEY,
-    ensure_contract_entry, get_program_version, read_json_file_from_path, set_program_version,
-    try_infer_program_id_from_env, write_json_to_file_path,
+    ensure_contract_entry, get_program_upgrade_authority, get_program_version,
+    read_json_file_from_path, set_artifact_hash_entry, set_program_artifact_hash,
+    set_program_version, try_infer_program_id_from_env, write_json_to_file_path,
 };
 
 #[derive(Args, Debug)]
 pub(crate) struct DeployArgs {
     /// Name of the program to deploy
     #[clap(long, value_enum)]
-    program: Programs,
+    pub(crate) program: Programs,
 
-    /// Path to the program keypair (determines program address)
+    /// Signer for the program address: keypair path or usb://ledger (determines program address)
     #[clap(long, env = "PROGRAM_KEYPAIR_PATH")]
-    program_keypair: String,
+    pub(crate) program_keypair: String,
 
-    /// Path to the upgrade authority keypair
+    /// Upgrade authority signer: keypair path or usb://ledger
     #[clap(long, env = "UPGRADE_AUTHORITY_KEYPAIR_PATH")]
-    upgrade_authority: String,
+    pub(crate) upgrade_authority: String,
 
-    /// Path to the fee payer keypair. Defaults to the Solana CLI default keypair.
+    /// Fee payer signer: keypair path or usb://ledger. Defaults to the Solana CLI default keypair.
     #[clap(long, env = "FEE_PAYER_KEYPAIR_PATH")]
-    fee_payer: Option<String>,
+    pub(crate) fee_payer: Option<String>,
 
-    /// Skip confirmation prompt
-    #[clap(short = 'y', long)]
-    yes: bool,
+    /// Signer of the buffer account to write the program to. Defaults to a buffer keypair kept in
+    /// the output directory, which lets an interrupted deployment resume where it stopped.
+    #[clap(long)]
+    pub(crate) buffer: Option<String>,
 
-    /// Path to the program bytecode (.so file)
-    #[clap(long, conflicts_with_all = &["version", "artifact-dir"])]
-    program_path: Option<String>,
+    /// Maximum length of the program data, defaults to the size of the program binary
+    #[clap(long)]
+    pub(crate) max_len: Option<usize>,
 
-    /// Version to download: semver (e.g., 0.1.7) from GitHub, or commit hash (e.g., 12e6126) from R2
-    #[clap(long, conflicts_with_all = &["program-path", "artifact-dir"])]
-    version: Option<String>,
+    /// Skip confirmation prompt
+    #[clap(short = 'y', long)]
+    pub(crate) yes: bool,
 
-    /// Directory containing local builds (e.g., ./target/deploy)
-    #[clap(long, conflicts_with_all = &["program-path", "version"])]
-    artifact_dir: Option<PathBuf>,
+    #[clap(flatten)]
+    pub(crate) artifact: ArtifactArgs,
 }
 
 pub(crate) async fn deploy_program(args: DeployArgs, config: crate::Config) -> Result<()> {
-    let program_path = artifact::resolve_program_path(
-        &args.program,
-        args.program_path.as_deref(),
-        args.version.as_deref(),
-        args.artifact_dir.as_deref(),
-        config.axelar_network,
-    )
-    .await?;
+    let program_path =
+        artifact::resolve_program_path(&args.program, &args.artifact, &config).await?;
+    let program_data = program_loader::read_program_file(&program_path)?;
+    let artifact_hash = artifact::sha256_hex(&program_data);
+    let max_data_len = args.max_len.unwrap_or(program_data.len());
+    if max_data_len < program_data.len() {
+        eyre::bail!(
+            "--max-len {max_data_len} is smaller than the program ({} bytes)",
+            program_data.len()
+        );
+    }
 
-    let fee_payer_path = get_fee_payer_path(args.fee_payer.as_deref())?;
-    let upgrade_authority_pubkey = get_pubkey_from_keypair(&args.upgrade_authority)?;
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let fee_payer = load_fee_payer(args.fee_payer.as_deref())?;
+    let upgrade_authority = load_signer(&args.upgrade_authority, "upgrade-authority")?;
+    let program_signer = load_signer(&args.program_keypair, "program-keypair")?;
+    let program_id = program_signer.pubkey();
 
-    print_fee_payer_info(&fee_payer_path, &config.url)?;
-    println!("Upgrade authority: {upgrade_authority_pubkey}");
-    if let Some(version) = &args.version {
+    if program_loader::fetch_loader_account(&rpc_client, &program_id)
+        .await?
+        .is_some_and(|account| matches!(account, LoaderAccount::Program { .. }))
+    {
+        eyre::bail!("Program {program_id} is already deployed. Use the upgrade command instead.");
+    }
+
+    print_fee_payer_info(&rpc_client, &fee_payer.pubkey()).await;
+    println!("Upgrade authority: {}", upgrade_authority.pubkey());
+    if let Some(version) = &args.artifact.version {
         println!("Version: {version}");
     }
     println!(
-        "Deploying program {:?} using keypair {}",
-        args.program, args.program_keypair
+        "Program size: {} bytes (max length {max_data_len} bytes)",
+        program_data.len()
     );
+    println!("Program SHA-256: {artifact_hash}");
+    println!("Deploying program {:?} to {program_id}", args.program);
 
     if !args.yes && !confirm_action()? {
         println!("Aborted.");
         return Ok(());
     }
 
-    let status = Command::new("solana")
-        .arg("program")
-        .arg("deploy")
-        .arg("--url")
-        .arg(&config.url)
-        .arg("--program-id")
-        .arg(&args.program_keypair)
-        .arg("--keypair")
-        .arg(&fee_payer_path)
-        .arg("--upgrade-authority")
-        .arg(&args.upgrade_authority)
-        .arg(program_path.to_string_lossy().as_ref())
-        .status()?;
-
-    if !status.success() {
-        return Err(eyre::eyre!("solana program deploy failed"));
+    let buffer = load_buffer(args.buffer.as_deref(), &config, &args.program)?;
+    let deployed = async {
+        program_loader::stage_buffer(
+            &rpc_client,
+            fee_payer.as_ref(),
+            buffer.signer(),
+            &upgrade_authority.pubkey(),
+            &program_data,
+        )
+        .await?;
+
+        program_loader::deploy_from_buffer(
+            &rpc_client,
+            fee_payer.as_ref(),
+            program_signer.as_ref(),
+            &buffer.pubkey(),
+            upgrade_authority.as_ref(),
+            max_data_len,
+        )
+        .await
     }
+    .await;
+
+    let signature = match deployed {
+        Ok(signature) => signature,
+        Err(err) => {
+            buffer.print_recovery_hint();
+            return Err(err);
+        }
+    };
+    buffer.finish()?;
+    println!("Deploy transaction: {signature}");
 
     // Update chains info file with program address, upgrade authority, and version
     {
         let mut env: Value = read_json_file_from_path(&config.chains_info_file)?;
         let program_key = program_key_from_program(&args.program);
-        let program_pubkey = get_pubkey_from_keypair(&args.program_keypair)?;
+        let upgrade_authority_pubkey = upgrade_authority.pubkey();
 
         ensure_contract_entry(&mut env, &config.chain, program_key);
 
@@ -113,17 +147,19 @@ pub(crate) async fn deploy_program(args: DeployArgs, config: crate::Config) -> R
             ))
             .expect("contract entry was just created");
 
-        contracts[ADDRESS_KEY] = Value::String(program_pubkey.to_string());
+        contracts[ADDRESS_KEY] = Value::String(program_id.to_string());
         contracts[UPGRADE_AUTHORITY_KEY] = Value::String(upgrade_authority_pubkey.to_string());
+        set_artifact_hash_entry(contracts, &artifact_hash, args.artifact.version.as_deref());
 
-        if let Some(version) = &args.version {
+        if let Some(version) = &args.artifact.version {
             contracts[VERSION_KEY] = Value::String(version.clone());
         }
 
         write_json_to_file_path(&env, &config.chains_info_file)?;
         println!(
-            "Updated chains info: {program_key} address={program_pubkey}, upgradeAuthority={upgrade_authority_pubkey}{}",
-            args.version
+            "Updated chains info: {program_key} address={program_id}, upgradeAuthority={upgrade_authority_pubkey}, artifactHash={artifact_hash}{}",
+            args.artifact
+                .version
                 .as_ref()
                 .map(|v| format!(", version={v}"))
                 .unwrap_or_default()
@@ -140,40 +176,32 @@ pub(crate) struct UpgradeArgs {
     #[clap(long, value_enum)]
     program: Programs,
 
-    /// Path to the upgrade authority keypair
+    /// Upgrade authority signer: keypair path or usb://ledger
     #[clap(long, env = "UPGRADE_AUTHORITY_KEYPAIR_PATH")]
     upgrade_authority: String,
 
-    /// Path to the fee payer keypair. Defaults to the Solana CLI default keypair.
+    /// Fee payer signer: keypair path or usb://ledger. Defaults to the Solana CLI default keypair.
     #[clap(long, env = "FEE_PAYER_KEYPAIR_PATH")]
     fee_payer: Option<String>,
 
+    /// Signer of the buffer account to write the program to. Defaults to a buffer keypair kept in
+    /// the output directory, which lets an interrupted upgrade resume where it stopped.
+    #[clap(long)]
+    buffer: Option<String>,
+
     /// Skip confirmation prompt
     #[clap(short = 'y', long)]
     yes: bool,
 
-    /// Path to the program bytecode (.so file)
-    #[clap(long, conflicts_with_all = &["version", "artifact-dir"])]
-    program_path: Option<String>,
-
-    /// Version to download: semver (e.g., 0.1.7) from GitHub, or commit hash (e.g., 12e6126) from R2
-    #[clap(long, conflicts_with_all = &["program-path", "artifact-dir"])]
-    version: Option<String>,
-
-    /// Directory containing local builds (e.g., ./target/deploy)
-    #[clap(long, conflicts_with_all = &["program-path", "version"])]
-    artifact_dir: Option<PathBuf>,
+    #[clap(flatten)]
+    artifact: ArtifactArgs,
 }
 
 pub(crate) async fn upgrade_program(args: UpgradeArgs, config: crate::Config) -> Result<()> {
-    let program_path = artifact::resolve_program_path(
-        &args.program,
-        args.program_path.as_deref(),
-        args.version.as_deref(),
-        args.artifact_dir.as_deref(),
-        config.axelar_network,
-    )
-    .await?;
+    let program_path =
+        artifact::resolve_program_path(&args.program, &args.artifact, &config).await?;
+    let program_data = program_loader::read_program_file(&program_path)?;
+    let artifact_hash = artifact::sha256_hex(&program_data);
 
     let mut env: Value = read_json_file_from_path(&config.chains_info_file)?;
     let chain = &config.chain;
@@ -183,7 +211,7 @@ pub(crate) async fn upgrade_program(args: UpgradeArgs, config: crate::Config) ->
 
     // Check if version matches the current deployed version
     let current_version = get_program_version(&env, chain, program_key);
-    let new_version = args.version.as_deref();
+    let new_version = args.artifact.version.as_deref();
 
     if let (Some(current), Some(new)) = (&current_version, new_version) {
         if current == new {
@@ -202,59 +230,201 @@ pub(crate) async fn upgrade_program(args: UpgradeArgs, config: crate::Config) ->
         }
     }
 
-    let fee_payer_path = get_fee_payer_path(args.fee_payer.as_deref())?;
-    let upgrade_authority_pubkey = get_pubkey_from_keypair(&args.upgrade_authority)?;
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let fee_payer = load_fee_payer(args.fee_payer.as_deref())?;
+    let upgrade_authority = load_signer(&args.upgrade_authority, "upgrade-authority")?;
 
-    print_fee_payer_info(&fee_payer_path, &config.url)?;
-    println!("Upgrade authority: {upgrade_authority_pubkey}");
+    print_fee_payer_info(&rpc_client, &fee_payer.pubkey()).await;
+    println!("Upgrade authority: {}", upgrade_authority.pubkey());
     if let Some(current) = &current_version {
         println!("Current version: {current}");
     }
     if let Some(new) = new_version {
         println!("New version: {new}");
     }
+    println!("Program size: {} bytes", program_data.len());
+    println!("Program SHA-256: {artifact_hash}");
     println!(
         "Upgrading program {:?} with ID {}",
         args.program, program_id
     );
 
+    // Checked before staging the buffer, so that a wrong authority doesn't cost the buffer rent.
+    program_loader::ensure_upgrade_authority(&rpc_client, &program_id, &upgrade_authority.pubkey())
+        .await?;
+
     if !args.yes && !confirm_action()? {
         println!("Aborted.");
         return Ok(());
     }
 
-    // Build the solana program deploy command
-    let status = Command::new("solana")
-        .arg("program")
-        .arg("deploy")
-        .arg("--url")
-        .arg(&config.url)
-        .arg("--program-id")
-        .arg(program_id.to_string())
-        .arg("--keypair")
-        .arg(&fee_payer_path)
-        .arg("--upgrade-authority")
-        .arg(&args.upgrade_authority)
-        .arg(program_path.to_string_lossy().as_ref())
-        .status()?;
-
-    if !status.success() {
-        return Err(eyre::eyre!("solana program upgrade failed"));
+    let buffer = load_buffer(args.buffer.as_deref(), &config, &args.program)?;
+    let upgraded = async {
+        program_loader::stage_buffer(
+            &rpc_client,
+            fee_payer.as_ref(),
+            buffer.signer(),
+            &upgrade_authority.pubkey(),
+            &program_data,
+        )
+        .await?;
+
+        let instructions = program_loader::upgrade_instructions(
+            &rpc_client,
+            &program_id,
+            &buffer.pubkey(),
+            &upgrade_authority.pubkey(),
+            &fee_payer.pubkey(),
+            &fee_payer.pubkey(),
+            program_data.len(),
+        )
+        .await?;
+
+        println!(
+            "Upgrading program {program_id} from buffer {}",
+            buffer.pubkey()
+        );
+        program_loader::send_and_confirm(
+            &rpc_client,
+            &instructions,
+            &[fee_payer.as_ref(), upgrade_authority.as_ref()],
+            fee_payer.as_ref(),
+        )
+        .await
     }
+    .await;
 
-    // Update version in chains info file if version was provided
+    let signature = match upgraded {
+        Ok(signature) => signature,
+        Err(err) => {
+            buffer.print_recovery_hint();
+            return Err(err);
+        }
+    };
+    buffer.finish()?;
+    println!("Upgrade transaction: {signature}");
+
+    // Record the deployed artifact hash, and the version if one was provided
+    set_program_artifact_hash(&mut env, chain, program_key, &artifact_hash, new_version)?;
     if let Some(version) = new_version {
         set_program_version(&mut env, chain, program_key, version)?;
-        write_json_to_file_path(&env, &config.chains_info_file)?;
         println!("Updated version to {version} in chains info file.");
     }
+    write_json_to_file_path(&env, &config.chains_info_file)?;
+    println!("Updated artifact hash to {artifact_hash} in chains info file.");
 
     println!("Program {:?} upgraded successfully.", args.program);
     Ok(())
 }
 
+#[derive(Args, Debug)]
+pub(crate) struct WriteBufferArgs {
+    /// Name of the program to write
+    #[clap(long, value_enum)]
+    program: Programs,
+
+    /// Upgrade authority the buffer is handed over to. Defaults to the upgrade authority in the
+    /// chains info file.
+    #[clap(long)]
+    upgrade_authority: Option<Pubkey>,
+
+    /// Fee payer signer: keypair path or usb://ledger. Writes the buffer before handing it over.
+    /// Defaults to the Solana CLI default keypair.
+    #[clap(long, env = "FEE_PAYER_KEYPAIR_PATH")]
+    fee_payer: Option<String>,
+
+    /// Signer of the buffer account to write the program to. Defaults to a buffer keypair kept in
+    /// the output directory, which lets an interrupted write resume where it stopped.
+    #[clap(long)]
+    buffer: Option<String>,
+
+    /// Skip confirmation prompt
+    #[clap(short = 'y', long)]
+    yes: bool,
+
+    #[clap(flatten)]
+    artifact: ArtifactArgs,
+}
+
+/// First phase of an offline upgrade: write the program to a buffer with a hot key and hand the
+/// buffer over to the (cold) upgrade authority, which then signs the upgrade transaction
+/// generated with `generate program upgrade`.
+pub(crate) async fn write_buffer(args: WriteBufferArgs, config: crate::Config) -> Result<()> {
+    let program_path =
+        artifact::resolve_program_path(&args.program, &args.artifact, &config).await?;
+    let program_data = program_loader::read_program_file(&program_path)?;
+    let artifact_hash = artifact::sha256_hex(&program_data);
+
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let program_key = program_key_from_program(&args.program);
+    let program_id = try_infer_program_id_from_env(&env, &config.chain, program_key)?;
+    let upgrade_authority = args
+        .upgrade_authority
+        .or_else(|| get_program_upgrade_authority(&env, &config.chain, program_key))
+        .ok_or_else(|| {
+            eyre!("No --upgrade-authority provided and none found for {program_key} in the chains info file")
+        })?;
+
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let fee_payer = load_fee_payer(args.fee_payer.as_deref())?;
+
+    print_fee_payer_info(&rpc_client, &fee_payer.pubkey()).await;
+    println!("Upgrade authority: {upgrade_authority}");
+    if let Some(version) = &args.artifact.version {
+        println!("Version: {version}");
+    }
+    println!("Program size: {} bytes", program_data.len());
+    println!("Program SHA-256: {artifact_hash}");
+    println!(
+        "Writing program {:?} ({program_id}) to a buffer for {upgrade_authority}",
+        args.program
+    );
+
+    if !args.yes && !confirm_action()? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let buffer = load_buffer(args.buffer.as_deref(), &config, &args.program)?;
+    if let Err(err) = program_loader::stage_buffer(
+        &rpc_client,
+        fee_payer.as_ref(),
+        buffer.signer(),
+        &upgrade_authority,
+        &program_data,
+    )
+    .await
+    {
+        buffer.print_recovery_hint();
+        return Err(err);
+    }
+
+    let buffer_address = buffer.pubkey();
+    buffer.finish()?;
+
+    println!("------------------------------------------");
+    println!("\u{2705} Program written to buffer {buffer_address}, owned by {upgrade_authority}");
+    println!("Generate the upgrade transaction for the upgrade authority to sign with:");
+    println!(
+        "   generate --fee-payer <PUBKEY> --nonce-account <PUBKEY> --nonce-authority <PUBKEY> program upgrade --program {} --buffer {buffer_address}{}",
+        args.program
+            .to_possible_value()
+            .map_or("<PROGRAM>", |v| v.get_name()),
+        args.artifact
+            .version
+            .as_ref()
+            .map(|v| format!(" --version {v}"))
+            .unwrap_or_default()
+    );
+    println!("------------------------------------------");
+
+    Ok(())
+}
+
 /// Get the JSON key for a program.
-fn program_key_from_program(program: &Programs) -> &'static str {
+pub(crate) fn program_key_from_program(program: &Programs) -> &'static str {
     match program {
         Programs::Gateway => GATEWAY_KEY,
         Programs::GasService => GAS_SERVICE_KEY,
@@ -266,69 +436,69 @@ fn program_key_from_program(program: &Programs) -> &'static str {
     }
 }
 
-/// Get fee payer keypair path. If not provided, uses the default Solana CLI keypair.
-fn get_fee_payer_path(fee_payer: Option<&str>) -> Result<String> {
+/// Load a signer from a keypair path, usb://ledger or any other signer URI supported by the
+/// Solana CLI.
+pub(crate) fn load_signer(path: &str, name: &str) -> Result<Box<dyn Signer>> {
+    let signer_context = clap::ArgMatches::default();
+    signer_from_path(&signer_context, path, name, &mut None)
+        .map_err(|e| eyre!("Failed to load {name} signer '{path}': {e}"))
+}
+
+/// Load the fee payer signer. If not provided, uses the default Solana CLI keypair.
+pub(crate) fn load_fee_payer(fee_payer: Option<&str>) -> Result<Box<dyn Signer>> {
     if let Some(path) = fee_payer {
-        return Ok(path.to_owned());
+        return load_signer(path, "fee-payer");
     }
 
     // Get default keypair path from solana config
     let solana_config = solana_cli_config::CONFIG_FILE
         .as_ref()
         .and_then(|config_file| solana_cli_config::Config::load(config_file).ok())
-        .ok_or_else(|| eyre::eyre!("No fee payer provided and no Solana CLI config found"))?;
+        .ok_or_else(|| eyre!("No fee payer provided and no Solana CLI config found"))?;
 
-    Ok(solana_config.keypair_path)
+    load_signer(&solana_config.keypair_path, "fee-payer")
 }
 
-/// Get the public key from a keypair file path.
-fn get_pubkey_from_keypair(keypair_path: &str) -> Result<Pubkey> {
-    let keypair = read_keypair_file(keypair_path)
-        .map_err(|e| eyre::eyre!("Failed to read keypair from {}: {}", keypair_path, e))?;
-    Ok(keypair.pubkey())
+/// Resolve the buffer to write the program to: either the provided signer or a buffer keypair
+/// managed in the output directory.
+fn load_buffer(
+    buffer: Option<&str>,
+    config: &crate::Config,
+    program: &Programs,
+) -> Result<BufferSource> {
+    match buffer {
+        Some(path) => Ok(BufferSource::Provided(load_signer(path, "buffer")?)),
+        None => BufferSource::managed(
+            &config.output_dir,
+            &format!(
+                "{}-{}",
+                artifact::program_to_so_filename(program),
+                config.chain
+            ),
+        ),
+    }
 }
 
 /// Print fee payer information (address and balance).
-fn print_fee_payer_info(fee_payer_path: &str, rpc_url: &str) -> Result<()> {
-    let path = Path::new(fee_payer_path);
-
-    if !path.exists() {
-        eprintln!("WARNING: Fee payer keypair file does not exist: {fee_payer_path}");
-        return Ok(());
+pub(crate) async fn print_fee_payer_info(rpc_client: &RpcClient, fee_payer: &Pubkey) {
+    println!("Fee payer: {fee_payer}");
+
+    match rpc_client.get_balance(fee_payer).await {
+        Ok(lamports) => println!(
+            "Fee payer balance: {} SOL",
+            program_loader::format_sol(lamports)
+        ),
+        Err(err) => eprintln!("WARNING: Could not fetch fee payer balance: {err}"),
     }
-
-    let pubkey = get_pubkey_from_keypair(fee_payer_path)?;
-    println!("Fee payer: {pubkey}");
-
-    // Get balance using solana CLI
-    let output = Command::new("solana")
-        .arg("balance")
-        .arg(pubkey.to_string())
-        .arg("--url")
-        .arg(rpc_url)
-        .output()?;
-
-    if output.status.success() {
-        let balance = String::from_utf8_lossy(&output.stdout);
-        println!("Fee payer balance: {}", balance.trim());
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        eprintln!(
-            "WARNING: Could not fetch fee payer balance: {}",
-            stderr.trim()
-        );
-    }
-
-    Ok(())
 }
 
 /// Prompt the user for confirmation. Returns true if they confirm.
-fn confirm_action() -> Result<bool> {
+pub(crate) fn confirm_action() -> Result<bool> {
     confirm_action_with_message("Proceed?")
 }
 
 /// Prompt the user for confirmation with a custom message. Returns true if they confirm.
-fn confirm_action_with_message(message: &str) -> Result<bool> {
+pub(crate) fn confirm_action_with_message(message: &str) -> Result<bool> {
     print!("\n{message} [y/N] ");
     io::stdout().flush()?;
 
@@ -0,0 +1,463 @@
+//! Field-by-field comparison of the Solana contract entries of the chains info file with the
+//! on-chain state of the programs and their configuration accounts.
+
+use anchor_lang::AccountDeserialize;
+use clap::{Parser, ValueEnum};
+use serde_json::{Value, json};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::artifact;
+use crate::config::Config;
+use crate::deploy::program_key_from_program;
+use crate::program_loader::{self, LoaderAccount};
+use crate::types::Programs;
+use crate::utils::{
+    ARTIFACT_HASH_KEY, CHAINS_KEY, CONFIG_ACCOUNT_KEY, CONTRACTS_KEY, DOMAIN_SEPARATOR_KEY,
+    GAS_SERVICE_KEY, GATEWAY_KEY, GOVERNANCE_ADDRESS_KEY, GOVERNANCE_CHAIN_KEY, GOVERNANCE_KEY,
+    ITS_KEY, MINIMUM_PROPOSAL_ETA_DELAY_KEY, MINIMUM_ROTATION_DELAY_KEY, OPERATOR_KEY,
+    OPERATORS_KEY, OWNER_KEY, PREVIOUS_SIGNERS_RETENTION_KEY, UPGRADE_AUTHORITY_KEY,
+    read_json_file_from_path, try_infer_program_id_from_env, write_json_to_file_path,
+};
+
+#[derive(Parser, Debug)]
+pub(crate) struct DriftArgs {
+    /// Write the on-chain values of the drifted fields back into the chains info file
+    #[clap(long)]
+    fix_config: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    InSync,
+    Drifted,
+    /// The field is not recorded in the chains info file
+    Missing,
+}
+
+/// Comparison of one field of a contract entry with its on-chain value.
+#[derive(Debug)]
+struct FieldCheck {
+    contract: &'static str,
+    field: &'static str,
+    recorded: Value,
+    onchain: Value,
+    status: Status,
+    /// Whether `onchain` can be written as-is to the chains info file
+    fixable: bool,
+}
+
+impl FieldCheck {
+    /// Compare a recorded value with the on-chain one, in the representation used by the chains
+    /// info file.
+    fn new(contract: &'static str, field: &'static str, entry: &Value, onchain: Value) -> Self {
+        let recorded = entry.get(field).cloned().unwrap_or(Value::Null);
+        let status = if recorded.is_null() && !onchain.is_null() {
+            Status::Missing
+        } else if normalize(&recorded) == normalize(&onchain) {
+            Status::InSync
+        } else {
+            Status::Drifted
+        };
+
+        Self {
+            contract,
+            field,
+            recorded,
+            status,
+            // A missing account or value on-chain is reported but never written back.
+            fixable: !onchain.is_null(),
+            onchain,
+        }
+    }
+
+    /// A field whose on-chain value can only be checked against the recorded one (e.g. a hash of
+    /// it), and therefore can't be fixed.
+    fn verified(
+        contract: &'static str,
+        field: &'static str,
+        entry: &Value,
+        onchain: Value,
+        matches: impl FnOnce(&str) -> bool,
+    ) -> Self {
+        let recorded = entry.get(field).cloned().unwrap_or(Value::Null);
+        let status = match recorded.as_str() {
+            None => Status::Missing,
+            Some(value) if matches(value) => Status::InSync,
+            Some(_) => Status::Drifted,
+        };
+
+        Self {
+            contract,
+            field,
+            recorded,
+            onchain,
+            status,
+            fixable: false,
+        }
+    }
+}
+
+/// Numbers are recorded either as JSON numbers or strings depending on the tool that wrote them.
+fn normalize(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_lowercase(),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::Array(_) | Value::Object(_) => {
+            value.to_string()
+        }
+    }
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_owned(),
+        Value::String(s) => s.clone(),
+        Value::Bool(_) | Value::Number(_) | Value::Array(_) | Value::Object(_) => value.to_string(),
+    }
+}
+
+async fn fetch_account_data(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+) -> eyre::Result<Option<Vec<u8>>> {
+    Ok(rpc_client
+        .get_account_with_commitment(address, rpc_client.commitment())
+        .await?
+        .value
+        .map(|account| account.data))
+}
+
+fn pda_value(pda: &Pubkey, exists: bool) -> Value {
+    if exists {
+        Value::String(pda.to_string())
+    } else {
+        Value::Null
+    }
+}
+
+pub(crate) async fn drift(args: DriftArgs, config: &Config) -> eyre::Result<()> {
+    let mut env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+
+    let mut checks = Vec::new();
+    for program in Programs::value_variants() {
+        let program_key = program_key_from_program(program);
+        let Ok(program_id) = try_infer_program_id_from_env(&env, &config.chain, program_key) else {
+            continue;
+        };
+        let entry = &env[CHAINS_KEY][&config.chain][CONTRACTS_KEY][program_key];
+
+        checks.extend(check_program(&rpc_client, program_key, &program_id, entry).await?);
+        checks.extend(check_config(&rpc_client, program, entry).await?);
+    }
+
+    if checks.is_empty() {
+        println!(
+            "No Solana contracts found for chain {} in the chains info file",
+            config.chain
+        );
+        return Ok(());
+    }
+
+    let mut drifted = 0_usize;
+    let mut current_contract = "";
+    for check in &checks {
+        if check.contract != current_contract {
+            current_contract = check.contract;
+            println!("{current_contract}:");
+        }
+
+        let marker = match check.status {
+            Status::InSync => "\u{2705}",
+            Status::Drifted => "\u{274c}",
+            Status::Missing => "\u{26a0}\u{fe0f}",
+        };
+        if check.status != Status::InSync {
+            drifted = drifted.saturating_add(1);
+        }
+        println!("   {marker} {}", check.field);
+        println!("      Chains info: {}", display(&check.recorded));
+        println!("      On-chain:    {}", display(&check.onchain));
+    }
+
+    println!("------------------------------------------");
+    if drifted == 0 {
+        println!("\u{2705} Chains info matches the on-chain state");
+        return Ok(());
+    }
+    println!("{drifted} field(s) differ from the on-chain state");
+
+    if !args.fix_config {
+        println!("Run with --fix-config to write the on-chain values to the chains info file");
+        return Ok(());
+    }
+
+    let mut fixed = 0_usize;
+    for check in checks
+        .into_iter()
+        .filter(|check| check.status != Status::InSync)
+    {
+        if !check.fixable {
+            println!(
+                "Skipping {}.{}: the on-chain value can't be recorded as-is",
+                check.contract, check.field
+            );
+            continue;
+        }
+        env[CHAINS_KEY][&config.chain][CONTRACTS_KEY][check.contract][check.field] = check.onchain;
+        fixed = fixed.saturating_add(1);
+    }
+    write_json_to_file_path(&env, &config.chains_info_file)?;
+    println!(
+        "\u{2705} Updated {fixed} field(s) in {}",
+        config.chains_info_file.display()
+    );
+
+    Ok(())
+}
+
+/// Compare the loader state of a program with its entry: upgrade authority and artifact hash.
+/// The version itself isn't stored on-chain; a drifted artifact hash means the deployed binary is
+/// not the one of the recorded version.
+async fn check_program(
+    rpc_client: &RpcClient,
+    program_key: &'static str,
+    program_id: &Pubkey,
+    entry: &Value,
+) -> eyre::Result<Vec<FieldCheck>> {
+    let Some(LoaderAccount::Program { .. }) =
+        program_loader::fetch_loader_account(rpc_client, program_id).await?
+    else {
+        return Ok(vec![FieldCheck {
+            contract: program_key,
+            field: "address",
+            recorded: Value::String(program_id.to_string()),
+            onchain: Value::Null,
+            status: Status::Drifted,
+            fixable: false,
+        }]);
+    };
+
+    let deployed = program_loader::fetch_deployed_program(rpc_client, program_id).await?;
+    let onchain_hash = artifact::sha256_hex(program_loader::deployed_binary(&deployed.data));
+
+    let mut authority_check = FieldCheck::new(
+        program_key,
+        UPGRADE_AUTHORITY_KEY,
+        entry,
+        deployed.upgrade_authority.map_or(Value::Null, |authority| {
+            Value::String(authority.to_string())
+        }),
+    );
+    // Immutable programs are recorded with a null upgrade authority.
+    authority_check.fixable = true;
+
+    let mut hash_check = FieldCheck::new(
+        program_key,
+        ARTIFACT_HASH_KEY,
+        entry,
+        Value::String(onchain_hash),
+    );
+    // The hash is paired with the recorded version, so it is only updated by deploys and upgrades.
+    hash_check.fixable = false;
+    Ok(vec![authority_check, hash_check])
+}
+
+/// Compare the configuration account of a program with its entry.
+#[allow(clippy::too_many_lines)]
+async fn check_config(
+    rpc_client: &RpcClient,
+    program: &Programs,
+    entry: &Value,
+) -> eyre::Result<Vec<FieldCheck>> {
+    let checks = match program {
+        Programs::Gateway => {
+            use solana_axelar_gateway::state::config::GatewayConfig;
+
+            let (config_pda, _) = GatewayConfig::find_pda();
+            let Some(data) = fetch_account_data(rpc_client, &config_pda).await? else {
+                return Ok(vec![FieldCheck::new(
+                    GATEWAY_KEY,
+                    OPERATOR_KEY,
+                    entry,
+                    Value::Null,
+                )]);
+            };
+            let gateway_config = GatewayConfig::try_deserialize(&mut data.as_slice())?;
+
+            vec![
+                FieldCheck::new(
+                    GATEWAY_KEY,
+                    OPERATOR_KEY,
+                    entry,
+                    Value::String(gateway_config.operator.to_string()),
+                ),
+                FieldCheck::new(
+                    GATEWAY_KEY,
+                    DOMAIN_SEPARATOR_KEY,
+                    entry,
+                    Value::String(format!(
+                        "0x{}",
+                        hex::encode(gateway_config.domain_separator)
+                    )),
+                ),
+                FieldCheck::new(
+                    GATEWAY_KEY,
+                    MINIMUM_ROTATION_DELAY_KEY,
+                    entry,
+                    json!(gateway_config.minimum_rotation_delay),
+                ),
+                FieldCheck::new(
+                    GATEWAY_KEY,
+                    PREVIOUS_SIGNERS_RETENTION_KEY,
+                    entry,
+                    serde_json::from_str(
+                        &gateway_config.previous_verifier_set_retention.to_string(),
+                    )?,
+                ),
+            ]
+        }
+        Programs::Its => {
+            let (its_root_pda, _) = crate::its::find_its_root_pda();
+            let exists = fetch_account_data(rpc_client, &its_root_pda)
+                .await?
+                .is_some();
+            let mut checks = vec![FieldCheck::new(
+                ITS_KEY,
+                CONFIG_ACCOUNT_KEY,
+                entry,
+                pda_value(&its_root_pda, exists),
+            )];
+
+            // The operator role is held in the user roles account of the operator.
+            if let Some(operator) = entry
+                .get(OPERATOR_KEY)
+                .and_then(Value::as_str)
+                .and_then(|s| s.parse::<Pubkey>().ok())
+            {
+                let (user_roles_pda, _) = Pubkey::find_program_address(
+                    &[b"user-roles", its_root_pda.as_ref(), operator.as_ref()],
+                    &solana_axelar_its::id(),
+                );
+                let has_roles = fetch_account_data(rpc_client, &user_roles_pda)
+                    .await?
+                    .is_some();
+                checks.push(FieldCheck::verified(
+                    ITS_KEY,
+                    OPERATOR_KEY,
+                    entry,
+                    Value::String(if has_roles {
+                        format!("{operator} (user roles account {user_roles_pda})")
+                    } else {
+                        format!("no user roles account for {operator}")
+                    }),
+                    |_| has_roles,
+                ));
+            }
+            checks
+        }
+        Programs::Governance => {
+            use solana_axelar_governance::GovernanceConfig;
+
+            let (config_pda, _) = GovernanceConfig::find_pda();
+            let Some(data) = fetch_account_data(rpc_client, &config_pda).await? else {
+                return Ok(vec![FieldCheck::new(
+                    GOVERNANCE_KEY,
+                    CONFIG_ACCOUNT_KEY,
+                    entry,
+                    Value::Null,
+                )]);
+            };
+            let governance_config = GovernanceConfig::try_deserialize(&mut data.as_slice())?;
+            let chain_hash = governance_config.chain_hash;
+            let address_hash = governance_config.address_hash;
+
+            vec![
+                FieldCheck::new(
+                    GOVERNANCE_KEY,
+                    CONFIG_ACCOUNT_KEY,
+                    entry,
+                    Value::String(config_pda.to_string()),
+                ),
+                FieldCheck::new(
+                    GOVERNANCE_KEY,
+                    OPERATOR_KEY,
+                    entry,
+                    Value::String(Pubkey::new_from_array(governance_config.operator).to_string()),
+                ),
+                FieldCheck::new(
+                    GOVERNANCE_KEY,
+                    MINIMUM_PROPOSAL_ETA_DELAY_KEY,
+                    entry,
+                    json!(governance_config.minimum_proposal_eta_delay),
+                ),
+                // Only the hashes of the governance chain and address are stored on-chain.
+                FieldCheck::verified(
+                    GOVERNANCE_KEY,
+                    GOVERNANCE_CHAIN_KEY,
+                    entry,
+                    Value::String(format!("keccak256 0x{}", hex::encode(chain_hash))),
+                    |chain| solana_sdk::keccak::hashv(&[chain.as_bytes()]).to_bytes() == chain_hash,
+                ),
+                FieldCheck::verified(
+                    GOVERNANCE_KEY,
+                    GOVERNANCE_ADDRESS_KEY,
+                    entry,
+                    Value::String(format!("keccak256 0x{}", hex::encode(address_hash))),
+                    |address| {
+                        solana_sdk::keccak::hashv(&[address.as_bytes()]).to_bytes() == address_hash
+                    },
+                ),
+            ]
+        }
+        Programs::GasService => {
+            let (treasury_pda, _) = Pubkey::find_program_address(
+                &[solana_axelar_gas_service::state::Treasury::SEED_PREFIX],
+                &solana_axelar_gas_service::id(),
+            );
+            let exists = fetch_account_data(rpc_client, &treasury_pda)
+                .await?
+                .is_some();
+            vec![FieldCheck::new(
+                GAS_SERVICE_KEY,
+                CONFIG_ACCOUNT_KEY,
+                entry,
+                pda_value(&treasury_pda, exists),
+            )]
+        }
+        Programs::Operators => {
+            use solana_axelar_operators::OperatorRegistry;
+
+            let (registry_pda, _) = OperatorRegistry::find_pda();
+            let Some(data) = fetch_account_data(rpc_client, &registry_pda).await? else {
+                return Ok(vec![FieldCheck::new(
+                    OPERATORS_KEY,
+                    CONFIG_ACCOUNT_KEY,
+                    entry,
+                    Value::Null,
+                )]);
+            };
+            let registry = OperatorRegistry::try_deserialize(&mut data.as_slice())?;
+
+            vec![
+                FieldCheck::new(
+                    OPERATORS_KEY,
+                    CONFIG_ACCOUNT_KEY,
+                    entry,
+                    Value::String(registry_pda.to_string()),
+                ),
+                FieldCheck::new(
+                    OPERATORS_KEY,
+                    OWNER_KEY,
+                    entry,
+                    Value::String(registry.owner.to_string()),
+                ),
+            ]
+        }
+        Programs::Memo | Programs::Multicall => Vec::new(),
+    };
+
+    Ok(checks)
+}
@@ -0,0 +1,934 @@
+//! Decoding of the Anchor `event-cpi` events emitted by the Axelar programs, and scanning of the
+//! history of a program for them.
+
+use std::fmt::Debug;
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine as _;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use eyre::eyre;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{
+    EncodedTransactionWithStatusMeta, UiInstruction, UiLoadedAddresses, UiTransactionEncoding,
+};
+
+use crate::config::Config;
+use crate::deploy::program_key_from_program;
+use crate::gateway::{GatewayEvent, parse_gateway_event};
+use crate::types::Programs;
+use crate::utils::{
+    GAS_SERVICE_KEY, GOVERNANCE_KEY, ITS_KEY, MEMO_KEY, OPERATORS_KEY, read_json_file_from_path,
+    try_infer_program_id_from_env, write_json_to_file_path,
+};
+
+/// Maximum number of signatures returned by one `getSignaturesForAddress` request.
+pub(crate) const SIGNATURES_PAGE_SIZE: usize = 1000;
+
+/// Decoder of one event type of an Axelar program other than the gateway.
+struct EventDecoder {
+    program: &'static str,
+    program_id: fn() -> Pubkey,
+    name: &'static str,
+    discriminator: &'static [u8],
+    decode: fn(&[u8]) -> eyre::Result<Box<dyn Debug>>,
+}
+
+impl EventDecoder {
+    const fn new<T: Discriminator + AnchorDeserialize + Debug + 'static>(
+        program: &'static str,
+        program_id: fn() -> Pubkey,
+        name: &'static str,
+    ) -> Self {
+        Self {
+            program,
+            program_id,
+            name,
+            discriminator: T::DISCRIMINATOR,
+            decode: decode_fields::<T>,
+        }
+    }
+}
+
+fn decode_fields<T: AnchorDeserialize + Debug + 'static>(
+    data: &[u8],
+) -> eyre::Result<Box<dyn Debug>> {
+    Ok(Box::new(T::deserialize(&mut &*data)?))
+}
+
+macro_rules! event_decoders {
+    ($($program:expr => $krate:ident [$($event:ident),+ $(,)?]),+ $(,)?) => {
+        &[$($(
+            EventDecoder::new::<$krate::$event>($program, $krate::id, stringify!($event)),
+        )+)+]
+    };
+}
+
+/// The events of the Axelar programs besides the gateway, whose events are decoded by
+/// `gateway::parse_gateway_event`.
+static EVENT_DECODERS: &[EventDecoder] = event_decoders![
+    ITS_KEY => solana_axelar_its [
+        InterchainTransfer,
+        InterchainTransferReceived,
+        InterchainTokenDeploymentStarted,
+        InterchainTokenDeployed,
+        TokenManagerDeployed,
+        LinkTokenStarted,
+        TokenMetadataRegistered,
+        InterchainTokenIdClaimed,
+        DeployRemoteInterchainTokenApproval,
+        RevokeRemoteInterchainTokenApproval,
+        FlowLimitSet,
+        TrustedChainSet,
+        TrustedChainRemoved,
+    ],
+    GAS_SERVICE_KEY => solana_axelar_gas_service [
+        GasPaidEvent,
+        GasAddedEvent,
+        GasRefundedEvent,
+        GasCollectedEvent,
+    ],
+    GOVERNANCE_KEY => solana_axelar_governance [
+        ProposalScheduled,
+        ProposalCancelled,
+        ProposalExecuted,
+        OperatorProposalApproved,
+        OperatorProposalCancelled,
+        OperatorProposalExecuted,
+        OperatorshipTransferred,
+    ],
+    OPERATORS_KEY => solana_axelar_operators [
+        OperatorAdded,
+        OperatorRemoved,
+        OwnershipTransferred,
+    ],
+    MEMO_KEY => solana_axelar_memo [MemoReceived],
+];
+
+/// An event emitted by one of the Axelar programs.
+pub(crate) enum AxelarEvent {
+    Gateway(GatewayEvent),
+    Program {
+        program: &'static str,
+        name: &'static str,
+        fields: Box<dyn Debug>,
+    },
+}
+
+impl AxelarEvent {
+    pub(crate) const fn program(&self) -> &'static str {
+        match self {
+            Self::Gateway(_) => crate::utils::GATEWAY_KEY,
+            Self::Program { program, .. } => program,
+        }
+    }
+
+    pub(crate) const fn name(&self) -> &'static str {
+        match self {
+            Self::Gateway(GatewayEvent::CallContract(_)) => "CallContractEvent",
+            Self::Gateway(GatewayEvent::MessageApproved(_)) => "MessageApprovedEvent",
+            Self::Gateway(GatewayEvent::MessageExecuted(_)) => "MessageExecutedEvent",
+            Self::Gateway(GatewayEvent::VerifierSetRotated(_)) => "VerifierSetRotatedEvent",
+            Self::Gateway(GatewayEvent::OperatorshipTransferred(_)) => {
+                "OperatorshipTransferredEvent"
+            }
+            Self::Program { name, .. } => name,
+        }
+    }
+}
+
+impl Debug for AxelarEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gateway(event) => event.fmt(f),
+            Self::Program { name, fields, .. } => {
+                if f.alternate() {
+                    write!(f, "{name}({fields:#?})")
+                } else {
+                    write!(f, "{name}({fields:?})")
+                }
+            }
+        }
+    }
+}
+
+/// Decode the data of an `event-cpi` instruction invoking `program_id`, with the decoders of
+/// that program only.
+#[allow(clippy::missing_asserts_for_indexing)]
+pub(crate) fn decode_event(program_id: &Pubkey, data: &[u8]) -> eyre::Result<Option<AxelarEvent>> {
+    if *program_id == solana_axelar_gateway::id() {
+        return Ok(parse_gateway_event(data)?.map(AxelarEvent::Gateway));
+    }
+    if data.len() < 16 || data[0..8] != anchor_lang::event::EVENT_IX_TAG_LE {
+        return Ok(None);
+    }
+
+    let Some(decoder) = EVENT_DECODERS.iter().find(|decoder| {
+        (decoder.program_id)() == *program_id && decoder.discriminator == &data[8..16]
+    }) else {
+        return Ok(None);
+    };
+    let fields = (decoder.decode)(&data[16..])
+        .map_err(|e| eyre!("Failed to deserialize {}: {e}", decoder.name))?;
+
+    Ok(Some(AxelarEvent::Program {
+        program: decoder.program,
+        name: decoder.name,
+        fields,
+    }))
+}
+
+/// The account keys of a transaction, in the order its compiled instructions index them: the
+/// static keys followed by the writable and the readonly addresses loaded from lookup tables.
+pub(crate) fn transaction_account_keys(
+    transaction: &EncodedTransactionWithStatusMeta,
+) -> eyre::Result<Vec<Pubkey>> {
+    let decoded = transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| eyre!("Failed to decode transaction"))?;
+    let mut account_keys = decoded.message.static_account_keys().to_vec();
+
+    let loaded_addresses: Option<UiLoadedAddresses> = transaction
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.loaded_addresses.clone().into());
+    if let Some(loaded_addresses) = loaded_addresses {
+        for address in loaded_addresses
+            .writable
+            .iter()
+            .chain(&loaded_addresses.readonly)
+        {
+            account_keys.push(Pubkey::from_str(address)?);
+        }
+    }
+
+    Ok(account_keys)
+}
+
+/// Decode the Axelar events emitted through self-CPI among the inner instructions of one
+/// invocation. `account_keys` resolves the program invoked by each inner instruction.
+pub(crate) fn decode_events(
+    account_keys: &[Pubkey],
+    inner_instructions: &[UiInstruction],
+) -> Vec<AxelarEvent> {
+    inner_instructions
+        .iter()
+        .filter_map(|inner_ix| {
+            let UiInstruction::Compiled(compiled_ix) = inner_ix else {
+                return None;
+            };
+            let program_id = account_keys.get(usize::from(compiled_ix.program_id_index))?;
+            let data = base64::engine::general_purpose::STANDARD
+                .decode(&compiled_ix.data)
+                .ok()?;
+
+            match decode_event(program_id, &data) {
+                Ok(event) => event,
+                Err(e) => {
+                    eprintln!("\u{26A0}\u{FE0F}  Warning: {e}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum QueryCommands {
+    /// Scan the transaction history of an Axelar program and export the events it emitted,
+    /// newest first
+    Scan(ScanArgs),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct ScanArgs {
+    /// Program whose transactions are scanned
+    #[clap(long, value_enum, default_value = "gateway")]
+    program: Programs,
+
+    #[clap(flatten)]
+    range: ScanRange,
+
+    /// Only export these event types (e.g. `CallContractEvent`, `GasPaidEvent`,
+    /// `InterchainTransfer`). Defaults to all of them.
+    #[clap(long)]
+    event: Vec<String>,
+
+    /// Only export `CallContract` events to this destination chain
+    #[clap(long)]
+    destination_chain: Option<String>,
+
+    /// Only export `CallContract` events sent by this account
+    #[clap(long)]
+    sender: Option<Pubkey>,
+
+    /// Output format
+    #[clap(long, value_enum, default_value = "jsonl")]
+    format: OutputFormat,
+
+    /// File the events are appended to. Defaults to the standard output.
+    #[clap(long)]
+    output: Option<PathBuf>,
+
+    /// File keeping track of the scan progress. An interrupted scan resumes where it stopped, and
+    /// a finished one only picks up newer transactions on the next run. A cursor only applies to
+    /// the slot and time range it was written for.
+    #[clap(long)]
+    cursor: Option<PathBuf>,
+}
+
+/// Slot and time range of a scan.
+#[derive(Args, Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct ScanRange {
+    /// Oldest slot to scan
+    #[clap(long)]
+    start_slot: Option<u64>,
+
+    /// Newest slot to scan
+    #[clap(long)]
+    end_slot: Option<u64>,
+
+    /// Oldest block time to scan (unix timestamp)
+    #[clap(long)]
+    start_time: Option<i64>,
+
+    /// Newest block time to scan (unix timestamp)
+    #[clap(long)]
+    end_time: Option<i64>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    Jsonl,
+    Csv,
+}
+
+/// Progress of a scan. Signatures are walked from the newest to the oldest.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ScanCursor {
+    /// Newest signature covered by the last finished scan; the next scan stops there.
+    #[serde(default)]
+    until: Option<String>,
+
+    /// Oldest signature processed by an interrupted scan; the next scan resumes below it.
+    #[serde(default)]
+    before: Option<String>,
+
+    /// Newest signature within the scanned range processed by the interrupted scan, which becomes
+    /// `until` once it finishes.
+    #[serde(default)]
+    newest: Option<String>,
+
+    /// Range the signatures above belong to
+    #[serde(default)]
+    range: ScanRange,
+}
+
+impl ScanCursor {
+    /// Move the cursor past a finished scan: the next one stops at the newest signature seen in
+    /// the range, or where the previous scan stopped if there was nothing new.
+    fn finish(&mut self) {
+        if let Some(newest) = self.newest.take() {
+            self.until = Some(newest);
+        }
+        self.before = None;
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct EventRecord {
+    signature: String,
+    slot: u64,
+    block_time: Option<i64>,
+    program: &'static str,
+    event: &'static str,
+    destination_chain: Option<String>,
+    sender: Option<String>,
+    details: String,
+}
+
+impl EventRecord {
+    const CSV_HEADER: &'static str =
+        "signature,slot,block_time,program,event,destination_chain,sender,details";
+
+    fn to_csv(&self) -> String {
+        [
+            self.signature.clone(),
+            self.slot.to_string(),
+            self.block_time.map(|t| t.to_string()).unwrap_or_default(),
+            self.program.to_owned(),
+            self.event.to_owned(),
+            self.destination_chain.clone().unwrap_or_default(),
+            self.sender.clone().unwrap_or_default(),
+            self.details.clone(),
+        ]
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+pub(crate) fn query(command: QueryCommands, config: &Config) -> eyre::Result<()> {
+    match command {
+        QueryCommands::Scan(args) => scan(&args, config),
+    }
+}
+
+/// Where the scan stands relative to the requested slot and time range.
+#[derive(Debug, PartialEq, Eq)]
+enum RangePosition {
+    Newer,
+    Within,
+    Older,
+}
+
+impl ScanRange {
+    fn position(&self, slot: u64, block_time: Option<i64>) -> RangePosition {
+        let older = self.start_slot.is_some_and(|start| slot < start)
+            || self
+                .start_time
+                .zip(block_time)
+                .is_some_and(|(start, time)| time < start);
+        let newer = self.end_slot.is_some_and(|end| slot > end)
+            || self
+                .end_time
+                .zip(block_time)
+                .is_some_and(|(end, time)| time > end);
+
+        if older {
+            RangePosition::Older
+        } else if newer {
+            RangePosition::Newer
+        } else {
+            RangePosition::Within
+        }
+    }
+}
+
+impl ScanArgs {
+    fn accepts(&self, event: &AxelarEvent) -> bool {
+        let name = event.name();
+        if !self.event.is_empty()
+            && !self.event.iter().any(|wanted| {
+                name.eq_ignore_ascii_case(wanted)
+                    || name
+                        .strip_suffix("Event")
+                        .is_some_and(|short| short.eq_ignore_ascii_case(wanted))
+            })
+        {
+            return false;
+        }
+        if self.destination_chain.is_none() && self.sender.is_none() {
+            return true;
+        }
+
+        match event {
+            AxelarEvent::Gateway(GatewayEvent::CallContract(call)) => {
+                self.destination_chain
+                    .as_ref()
+                    .is_none_or(|chain| call.destination_chain == *chain)
+                    && self.sender.is_none_or(|sender| call.sender == sender)
+            }
+            AxelarEvent::Gateway(
+                GatewayEvent::MessageApproved(_)
+                | GatewayEvent::MessageExecuted(_)
+                | GatewayEvent::VerifierSetRotated(_)
+                | GatewayEvent::OperatorshipTransferred(_),
+            )
+            | AxelarEvent::Program { .. } => false,
+        }
+    }
+}
+
+fn event_record(
+    signature: &str,
+    slot: u64,
+    block_time: Option<i64>,
+    event: &AxelarEvent,
+) -> EventRecord {
+    let (destination_chain, sender) = match event {
+        AxelarEvent::Gateway(GatewayEvent::CallContract(call)) => (
+            Some(call.destination_chain.clone()),
+            Some(call.sender.to_string()),
+        ),
+        AxelarEvent::Gateway(
+            GatewayEvent::MessageApproved(_)
+            | GatewayEvent::MessageExecuted(_)
+            | GatewayEvent::VerifierSetRotated(_)
+            | GatewayEvent::OperatorshipTransferred(_),
+        )
+        | AxelarEvent::Program { .. } => (None, None),
+    };
+
+    EventRecord {
+        signature: signature.to_owned(),
+        slot,
+        block_time,
+        program: event.program(),
+        event: event.name(),
+        destination_chain,
+        sender,
+        details: format!("{event:?}"),
+    }
+}
+
+fn open_output(args: &ScanArgs) -> eyre::Result<Box<dyn std::io::Write>> {
+    let Some(path) = &args.output else {
+        let mut stdout = std::io::stdout();
+        if args.format == OutputFormat::Csv {
+            writeln!(stdout, "{}", EventRecord::CSV_HEADER)?;
+        }
+        return Ok(Box::new(stdout));
+    };
+
+    let is_new = std::fs::metadata(path).map_or(true, |metadata| metadata.len() == 0);
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new && args.format == OutputFormat::Csv {
+        writeln!(file, "{}", EventRecord::CSV_HEADER)?;
+    }
+
+    Ok(Box::new(file))
+}
+
+/// Load the cursor at `path`, which must have been written for `range`: its signatures would skip
+/// or repeat part of another range.
+fn load_cursor(path: Option<&Path>, range: &ScanRange) -> eyre::Result<ScanCursor> {
+    let Some(path) = path.filter(|path| path.exists()) else {
+        return Ok(ScanCursor {
+            range: range.clone(),
+            ..ScanCursor::default()
+        });
+    };
+    let cursor: ScanCursor = read_json_file_from_path(path)?;
+    if cursor.range != *range {
+        eyre::bail!(
+            "The cursor {} was written for another range ({:?}). Scan this range with a new cursor \
+             file, or remove it to start over.",
+            path.display(),
+            cursor.range
+        );
+    }
+
+    Ok(cursor)
+}
+
+fn save_cursor(path: Option<&Path>, cursor: &ScanCursor) -> eyre::Result<()> {
+    match path {
+        Some(path) => write_json_to_file_path(cursor, path),
+        None => Ok(()),
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+fn scan(args: &ScanArgs, config: &Config) -> eyre::Result<()> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let program_id = try_infer_program_id_from_env(
+        &env,
+        &config.chain,
+        program_key_from_program(&args.program),
+    )?;
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+
+    let cursor_path = args.cursor.as_deref();
+    let mut cursor = load_cursor(cursor_path, &args.range)?;
+    if let Some(before) = &cursor.before {
+        eprintln!("Resuming the scan of {program_id} before {before}");
+    }
+
+    let mut output = open_output(args)?;
+    let mut scanned = 0_usize;
+    let mut exported = 0_usize;
+
+    loop {
+        let page = rpc_client.get_signatures_for_address_with_config(
+            &program_id,
+            GetConfirmedSignaturesForAddress2Config {
+                before: cursor
+                    .before
+                    .as_deref()
+                    .map(Signature::from_str)
+                    .transpose()?,
+                until: cursor
+                    .until
+                    .as_deref()
+                    .map(Signature::from_str)
+                    .transpose()?,
+                limit: Some(SIGNATURES_PAGE_SIZE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        let last_signature = last.signature.clone();
+
+        // Rows are only written once the whole page is processed, so that a scan interrupted
+        // mid-page resumes at the start of the page without exporting its events twice.
+        let mut rows = Vec::new();
+        let mut reached_start = false;
+        for status in &page {
+            match args.range.position(status.slot, status.block_time) {
+                RangePosition::Older => {
+                    reached_start = true;
+                    break;
+                }
+                RangePosition::Newer => continue,
+                RangePosition::Within => {}
+            }
+            if cursor.newest.is_none() {
+                cursor.newest = Some(status.signature.clone());
+            }
+            scanned = scanned.saturating_add(1);
+            if status.err.is_some() {
+                continue;
+            }
+
+            let transaction = rpc_client.get_transaction_with_config(
+                &Signature::from_str(&status.signature)?,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )?;
+            let account_keys = transaction_account_keys(&transaction.transaction)?;
+            let Some(meta) = transaction.transaction.meta else {
+                continue;
+            };
+
+            for inner_ix_set in meta.inner_instructions.unwrap_or_else(std::vec::Vec::new) {
+                for event in decode_events(&account_keys, &inner_ix_set.instructions) {
+                    if !args.accepts(&event) {
+                        continue;
+                    }
+                    let record =
+                        event_record(&status.signature, status.slot, status.block_time, &event);
+                    rows.push(match args.format {
+                        OutputFormat::Jsonl => serde_json::to_string(&record)?,
+                        OutputFormat::Csv => record.to_csv(),
+                    });
+                }
+            }
+        }
+
+        for row in &rows {
+            writeln!(output, "{row}")?;
+        }
+        output.flush()?;
+        exported = exported.saturating_add(rows.len());
+        if reached_start {
+            break;
+        }
+        cursor.before = Some(last_signature);
+        save_cursor(cursor_path, &cursor)?;
+        eprintln!("Scanned {scanned} transaction(s), exported {exported} event(s)");
+    }
+
+    output.flush()?;
+    cursor.finish();
+    save_cursor(cursor_path, &cursor)?;
+
+    eprintln!("------------------------------------------");
+    eprintln!("Scan of {program_id} finished: {scanned} transaction(s), {exported} event(s)");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_args(
+        event: &[&str],
+        destination_chain: Option<&str>,
+        sender: Option<Pubkey>,
+    ) -> ScanArgs {
+        ScanArgs {
+            program: Programs::Gateway,
+            range: ScanRange::default(),
+            event: event.iter().map(|name| (*name).to_owned()).collect(),
+            destination_chain: destination_chain.map(str::to_owned),
+            sender,
+            format: OutputFormat::Jsonl,
+            output: None,
+            cursor: None,
+        }
+    }
+
+    fn call_contract(destination_chain: &str, sender: Pubkey) -> AxelarEvent {
+        AxelarEvent::Gateway(GatewayEvent::CallContract(
+            solana_axelar_gateway::CallContractEvent {
+                sender,
+                payload_hash: [0; 32],
+                destination_chain: destination_chain.to_owned(),
+                destination_contract_address: "0xdead".to_owned(),
+                payload: vec![],
+            },
+        ))
+    }
+
+    fn program_event(name: &'static str) -> AxelarEvent {
+        AxelarEvent::Program {
+            program: ITS_KEY,
+            name,
+            fields: Box::new(()),
+        }
+    }
+
+    #[test]
+    fn test_range_position_slots() {
+        let range = ScanRange {
+            start_slot: Some(100),
+            end_slot: Some(200),
+            ..ScanRange::default()
+        };
+        assert_eq!(range.position(99, None), RangePosition::Older);
+        assert_eq!(range.position(100, None), RangePosition::Within);
+        assert_eq!(range.position(200, None), RangePosition::Within);
+        assert_eq!(range.position(201, None), RangePosition::Newer);
+
+        assert_eq!(
+            ScanRange::default().position(0, None),
+            RangePosition::Within
+        );
+    }
+
+    #[test]
+    fn test_range_position_times() {
+        let range = ScanRange {
+            start_time: Some(1_000),
+            end_time: Some(2_000),
+            ..ScanRange::default()
+        };
+        assert_eq!(range.position(5, Some(999)), RangePosition::Older);
+        assert_eq!(range.position(5, Some(1_000)), RangePosition::Within);
+        assert_eq!(range.position(5, Some(2_000)), RangePosition::Within);
+        assert_eq!(range.position(5, Some(2_001)), RangePosition::Newer);
+
+        // Without a block time only the slot bounds apply.
+        assert_eq!(range.position(5, None), RangePosition::Within);
+        let range = ScanRange {
+            start_slot: Some(10),
+            ..range
+        };
+        assert_eq!(range.position(5, None), RangePosition::Older);
+    }
+
+    #[test]
+    fn test_accepts_event_names() {
+        let sender = Pubkey::new_unique();
+        let event = call_contract("ethereum", sender);
+
+        assert!(scan_args(&[], None, None).accepts(&event));
+        assert!(scan_args(&["CallContractEvent"], None, None).accepts(&event));
+        assert!(scan_args(&["callcontract"], None, None).accepts(&event));
+        assert!(scan_args(&["MessageApproved", "CallContract"], None, None).accepts(&event));
+        assert!(!scan_args(&["MessageApproved"], None, None).accepts(&event));
+        assert!(!scan_args(&["CallContractEv"], None, None).accepts(&event));
+
+        let transfer = program_event("InterchainTransfer");
+        assert!(scan_args(&["InterchainTransfer"], None, None).accepts(&transfer));
+        assert!(!scan_args(&["InterchainTransferEvent"], None, None).accepts(&transfer));
+        assert!(!scan_args(&["Interchain"], None, None).accepts(&transfer));
+    }
+
+    #[test]
+    fn test_accepts_call_contract_filters() {
+        let sender = Pubkey::new_unique();
+        let event = call_contract("ethereum", sender);
+
+        assert!(scan_args(&[], Some("ethereum"), None).accepts(&event));
+        assert!(!scan_args(&[], Some("avalanche"), None).accepts(&event));
+        assert!(scan_args(&[], None, Some(sender)).accepts(&event));
+        assert!(!scan_args(&[], None, Some(Pubkey::new_unique())).accepts(&event));
+        assert!(scan_args(&[], Some("ethereum"), Some(sender)).accepts(&event));
+        assert!(!scan_args(&[], Some("ethereum"), Some(Pubkey::new_unique())).accepts(&event));
+
+        // The filters only match CallContract events.
+        assert!(!scan_args(&[], Some("ethereum"), None).accepts(&program_event("GasPaidEvent")));
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_cursor_finish() {
+        // An interrupted scan that saw new signatures: the next scan stops at the newest one.
+        let mut cursor = ScanCursor {
+            until: Some("old".to_owned()),
+            before: Some("oldest-processed".to_owned()),
+            newest: Some("new".to_owned()),
+            range: ScanRange::default(),
+        };
+        cursor.finish();
+        assert_eq!(cursor.until.as_deref(), Some("new"));
+        assert_eq!(cursor.before, None);
+        assert_eq!(cursor.newest, None);
+
+        // A scan that found nothing new keeps its stopping point.
+        let mut cursor = ScanCursor {
+            until: Some("old".to_owned()),
+            ..ScanCursor::default()
+        };
+        cursor.finish();
+        assert_eq!(cursor.until.as_deref(), Some("old"));
+        assert_eq!(cursor.before, None);
+    }
+
+    #[test]
+    fn test_load_cursor_range() {
+        let range = ScanRange {
+            start_slot: Some(100),
+            ..ScanRange::default()
+        };
+        assert_eq!(load_cursor(None, &range).unwrap().range, range);
+
+        let path = std::env::temp_dir().join(format!("scan-cursor-{}.json", std::process::id()));
+        let cursor = ScanCursor {
+            until: Some("sig".to_owned()),
+            range: range.clone(),
+            ..ScanCursor::default()
+        };
+        save_cursor(Some(&path), &cursor).unwrap();
+
+        let loaded = load_cursor(Some(&path), &range).unwrap();
+        assert_eq!(loaded.until.as_deref(), Some("sig"));
+        assert!(load_cursor(Some(&path), &ScanRange::default()).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    fn event_ix_data<T: Discriminator + anchor_lang::AnchorSerialize>(event: &T) -> Vec<u8> {
+        let mut data = anchor_lang::event::EVENT_IX_TAG_LE.to_vec();
+        data.extend_from_slice(T::DISCRIMINATOR);
+        event.serialize(&mut data).unwrap();
+        data
+    }
+
+    fn assert_round_trip<T: Discriminator + anchor_lang::AnchorSerialize + Debug>(
+        program_id: &Pubkey,
+        program: &str,
+        name: &str,
+        event: &T,
+    ) {
+        let decoded = decode_event(program_id, &event_ix_data(event))
+            .unwrap()
+            .unwrap_or_else(|| panic!("{name} was not decoded"));
+        assert_eq!(decoded.program(), program);
+        assert_eq!(decoded.name(), name);
+        assert_eq!(format!("{decoded:?}"), format!("{name}({event:?})"));
+    }
+
+    #[test]
+    fn test_decode_event_round_trip() {
+        assert_round_trip(
+            &solana_axelar_its::id(),
+            ITS_KEY,
+            "TrustedChainSet",
+            &solana_axelar_its::TrustedChainSet {
+                chain_name: "ethereum".to_owned(),
+            },
+        );
+        assert_round_trip(
+            &solana_axelar_gas_service::id(),
+            GAS_SERVICE_KEY,
+            "GasCollectedEvent",
+            &solana_axelar_gas_service::GasCollectedEvent {
+                receiver: Pubkey::new_unique(),
+                amount: 42,
+                spl_token_account: None,
+            },
+        );
+        assert_round_trip(
+            &solana_axelar_governance::id(),
+            GOVERNANCE_KEY,
+            "OperatorshipTransferred",
+            &solana_axelar_governance::OperatorshipTransferred {
+                old_operator: Pubkey::new_unique().to_bytes(),
+                new_operator: Pubkey::new_unique().to_bytes(),
+            },
+        );
+        assert_round_trip(
+            &solana_axelar_operators::id(),
+            OPERATORS_KEY,
+            "OperatorAdded",
+            &solana_axelar_operators::OperatorAdded {
+                operator: Pubkey::new_unique(),
+            },
+        );
+        assert_round_trip(
+            &solana_axelar_memo::id(),
+            MEMO_KEY,
+            "MemoReceived",
+            &solana_axelar_memo::MemoReceived {
+                memo: "hello".to_owned(),
+            },
+        );
+    }
+
+    #[test]
+    fn test_decode_event_under_other_program() {
+        let data = event_ix_data(&solana_axelar_its::TrustedChainSet {
+            chain_name: "ethereum".to_owned(),
+        });
+        assert!(
+            decode_event(&solana_axelar_its::id(), &data)
+                .unwrap()
+                .is_some()
+        );
+
+        for program_id in [
+            solana_axelar_gas_service::id(),
+            solana_axelar_memo::id(),
+            solana_axelar_gateway::id(),
+            Pubkey::new_unique(),
+        ] {
+            assert!(decode_event(&program_id, &data).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_decode_event_without_event_tag() {
+        let mut data = event_ix_data(&solana_axelar_its::TrustedChainSet {
+            chain_name: "ethereum".to_owned(),
+        });
+        data[0] ^= 1;
+        assert!(
+            decode_event(&solana_axelar_its::id(), &data)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            decode_event(&solana_axelar_its::id(), &data[..15])
+                .unwrap()
+                .is_none()
+        );
+    }
+}
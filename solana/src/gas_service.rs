@@ -26,7 +26,7 @@ pub(crate) struct InitArgs {
     /// The account to set as operator of the AxelarGasService program. This account will be able
     /// to withdraw funds from the AxelarGasService program and update the configuration.
     #[clap(short, long)]
-    operator: Pubkey,
+    pub(crate) operator: Pubkey,
 }
 
 #[derive(Parser, Debug)]
@@ -76,6 +76,7 @@ pub(crate) fn build_transaction(
             nonce_account: None,
             nonce_authority: None,
             blockhash_for_message: blockhash.to_string(),
+            pending_update: None,
         };
 
         // Create a serializable transaction
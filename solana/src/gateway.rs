@@ -1,8 +1,8 @@
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anchor_lang::InstructionData;
-use base64::Engine as _;
 use clap::{ArgGroup, Args, Parser, Subcommand};
 use cosmrs::proto::cosmwasm::wasm::v1::query_client;
 use eyre::eyre;
@@ -25,7 +25,7 @@ use solana_sdk::message::Message as SolanaMessage;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature as SolanaSignature;
 use solana_sdk::transaction::Transaction as SolanaTransaction;
-use solana_transaction_status::{UiInstruction, UiTransactionEncoding};
+use solana_transaction_status::UiTransactionEncoding;
 
 use crate::config::Config;
 use crate::multisig_prover_types::Uint128Extensions;
@@ -43,13 +43,12 @@ use crate::utils::{
 
 const SOLANA_GATEWAY_CONNECTION_TYPE: &str = "amplifier";
 
-fn command_id(source_chain: &str, message_id: &str) -> [u8; 32] {
+pub(crate) fn command_id(source_chain: &str, message_id: &str) -> [u8; 32] {
     solana_sdk::keccak::hashv(&[source_chain.as_bytes(), b"-", message_id.as_bytes()]).to_bytes()
 }
 
 #[derive(Debug)]
-#[allow(dead_code)]
-enum GatewayEvent {
+pub(crate) enum GatewayEvent {
     CallContract(solana_axelar_gateway::CallContractEvent),
     VerifierSetRotated(solana_axelar_gateway::VerifierSetRotatedEvent),
     OperatorshipTransferred(solana_axelar_gateway::OperatorshipTransferredEvent),
@@ -84,13 +83,21 @@ pub(crate) enum Commands {
     Execute(ExecuteArgs),
 }
 
+/// Long-running gateway services
+#[derive(Subcommand, Debug)]
+pub(crate) enum ServiceCommands {
+    /// Continuously relay completed MultisigProver proofs to the AxelarGateway program, resuming
+    /// from a saved cursor
+    Relay(crate::relay::RelayArgs),
+}
+
 /// Commands for querying gateway related data
 #[derive(Subcommand, Debug)]
 pub(crate) enum QueryCommands {
-    /// Get GatewayEvents from a transaction
+    /// Get the events emitted by the Axelar programs in a transaction
     Events(EventsArgs),
 
-    /// Query message status on Gateway
+    /// Query the status of one message, or of a list of messages, on the Gateway
     MessageStatus(MessageStatusArgs),
 
     /// Query gateway config (domain separator, operator, etc.)
@@ -101,6 +108,29 @@ pub(crate) enum QueryCommands {
 
     /// Compute verifier set merkle root from MultisigProver's current verifier set
     ComputeMerkleRoot,
+
+    /// Commands comparing the verifier sets of the gateway and the MultisigProver
+    #[clap(subcommand)]
+    VerifierSet(VerifierSetCommands),
+
+    /// List all verifier set trackers registered with the gateway
+    VerifierSetTrackers,
+
+    /// List the signature verification sessions of the gateway and their progress. The payload and
+    /// quorum of a session come from its transaction history, which costs a signature lookup and
+    /// a couple of transaction fetches per session.
+    VerificationSessions(VerificationSessionsArgs),
+
+    /// Fetch a completed proof from the MultisigProver and save its `execute_data` to a file, to
+    /// be submitted with `gateway submit-proof --execute-data`
+    FetchProof(FetchProofArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum VerifierSetCommands {
+    /// Report whether the gateway is behind the MultisigProver's verifier set, in sync, and which
+    /// previous verifier sets are about to expire
+    Status,
 }
 
 #[derive(Args, Debug)]
@@ -109,6 +139,25 @@ pub(crate) struct VerifierSetTrackerArgs {
     merkle_root: String,
 }
 
+#[derive(Args, Debug)]
+pub(crate) struct FetchProofArgs {
+    /// The session id associated with the proof
+    #[clap(long)]
+    multisig_session_id: u64,
+
+    /// File the hex encoded `execute_data` is written to. Defaults to
+    /// `execute-data-<session id>.hex` in the output directory.
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct VerificationSessionsArgs {
+    /// Only list the sessions that have not reached quorum
+    #[clap(long)]
+    pending: bool,
+}
+
 #[derive(Args, Debug)]
 pub(crate) struct EventsArgs {
     /// The transaction signature to get events from
@@ -120,12 +169,44 @@ pub(crate) struct EventsArgs {
 }
 
 #[derive(Args, Debug)]
+#[clap(
+    group(
+        ArgGroup::new("messages")
+        .args(&["message-id", "file"])
+        .multiple(false)
+        .required(true))
+    )
+]
 pub(crate) struct MessageStatusArgs {
     /// The name of the chain from which the message was sent as it is registered with Axelar
-    source_chain: String,
+    #[clap(requires = "message-id")]
+    source_chain: Option<String>,
 
     /// Message ID
+    #[clap(requires = "source-chain")]
+    message_id: Option<String>,
+
+    /// Payload of the message (hex) to check against the approved payload hash
+    #[clap(long, conflicts_with = "file")]
+    payload: Option<String>,
+
+    /// Destination program of the message, to show the signing PDA it validates the message
+    /// with
+    #[clap(long, conflicts_with = "file")]
+    destination_address: Option<Pubkey>,
+
+    /// JSON file with the messages to look up, each as
+    /// `{"source_chain": "...", "message_id": "...", "payload": "<hex, optional>"}`
+    #[clap(long)]
+    file: Option<PathBuf>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct MessageLookup {
+    source_chain: String,
     message_id: String,
+    #[serde(default)]
+    payload: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -142,27 +223,27 @@ pub(crate) struct MessageStatusArgs {
 pub(crate) struct InitArgs {
     /// Previous SignerSet retention
     #[clap(long)]
-    previous_signers_retention: u128,
+    pub(crate) previous_signers_retention: u128,
 
     /// Minimum delay between SignerSet rotations
     #[clap(long)]
-    minimum_rotation_delay: u64,
+    pub(crate) minimum_rotation_delay: u64,
 
     /// Optional hex string with secp256k1 compressed public key used to create the initial SignerSet
     #[clap(long)]
-    signer: Option<String>,
+    pub(crate) signer: Option<String>,
 
     /// Nonce to be used for the SignerSet, required if `signer` or `signers` is set
     #[clap(long)]
-    nonce: Option<u64>,
+    pub(crate) nonce: Option<u64>,
 
     /// An optional JSON containing a SignerSet
     #[clap(long)]
-    signer_set: Option<String>,
+    pub(crate) signer_set: Option<String>,
 
     /// Address of the AxelarGateway program operator
     #[clap(long)]
-    operator: Pubkey,
+    pub(crate) operator: Pubkey,
 }
 
 #[derive(Parser, Debug)]
@@ -175,9 +256,19 @@ pub(crate) struct CallContractArgs {
     #[clap(long)]
     destination_address: String,
 
-    /// The payload as expected by the destination contract as a hex encoded string
+    /// The payload as expected by the destination contract as a hex encoded string. For generic
+    /// executable programs this is the `AxelarMessagePayload` built with `misc build-axelar-message`.
     #[clap(long)]
     payload: String,
+
+    /// Native gas (in lamports) paid to the AxelarGasService for relaying the message, in the same
+    /// transaction as the contract call
+    #[clap(long)]
+    gas_amount: Option<u64>,
+
+    /// The address refunded with the unused gas. Defaults to the fee payer.
+    #[clap(long, requires = "gas-amount")]
+    refund_address: Option<Pubkey>,
 }
 
 #[derive(Parser, Debug)]
@@ -192,10 +283,27 @@ pub(crate) struct TransferOperatorshipArgs {
 }
 
 #[derive(Parser, Debug)]
+#[clap(
+    group(
+        ArgGroup::new("signing_set")
+        .args(&["signer", "signers-file"])
+        .multiple(false)
+        .required(true))
+    )
+]
 pub(crate) struct ApproveArgs {
     /// Hex string with secp256k1 private key of the signer used to generate the proof
     #[clap(long, value_parser=utils::parse_secret_key, value_hint=clap::ValueHint::AnyPath)]
-    signer: k256::SecretKey,
+    signer: Option<k256::SecretKey>,
+
+    /// JSON or TOML file with the weighted signers of the SignerSet and its quorum, e.g.
+    /// `{"quorum": 3, "signers": [{"secret": "<hex or key file>", "weight": 2}]}`
+    #[clap(long)]
+    signers_file: Option<PathBuf>,
+
+    /// Sign with signers whose weight stays below the quorum, to exercise the failure path
+    #[clap(long)]
+    under_sign: bool,
 
     /// Nonce associated with the SignerSet the signer is part of
     #[clap(long)]
@@ -229,13 +337,27 @@ pub(crate) struct ApproveArgs {
         .args(&["new-signer", "new-signer-set"])
         .multiple(false)
         .requires("nonce")
-        .required(false))
+        .required(false)),
+    group(
+        ArgGroup::new("signing_set")
+        .args(&["signer", "signers-file"])
+        .multiple(false)
+        .required(true))
     )
 ]
 pub(crate) struct RotateArgs {
     /// Hex string with secp256k1 private key of the signer used to generate the proof
     #[clap(long, value_parser=utils::parse_secret_key, value_hint=clap::ValueHint::AnyPath)]
-    signer: k256::SecretKey,
+    signer: Option<k256::SecretKey>,
+
+    /// JSON or TOML file with the weighted signers of the current SignerSet and its quorum, e.g.
+    /// `{"quorum": 3, "signers": [{"secret": "<hex or key file>", "weight": 2}]}`
+    #[clap(long)]
+    signers_file: Option<PathBuf>,
+
+    /// Sign with signers whose weight stays below the quorum, to exercise the failure path
+    #[clap(long)]
+    under_sign: bool,
 
     /// Nonce to be used for the SignerSet, required if `signer` or `signers` is set.
     #[clap(long)]
@@ -252,13 +374,45 @@ pub(crate) struct RotateArgs {
     /// The new nonce to be used for the new SignerSet, required if `new_signer` or `new_signers` is set
     #[clap(long)]
     new_nonce: Option<u64>,
+
+    #[clap(flatten)]
+    rotation_timing: RotationTimingArgs,
 }
 
 #[derive(Parser, Debug)]
+#[clap(
+    group(
+        ArgGroup::new("proof")
+        .args(&["multisig-session-id", "execute-data"])
+        .multiple(false)
+        .required(true))
+    )
+]
 pub(crate) struct SubmitProofArgs {
     /// The session id associated with the proof, used o query the MultisigProver
     #[clap(long)]
-    multisig_session_id: u64,
+    multisig_session_id: Option<u64>,
+
+    /// The proof's `execute_data` as a hex string, or a file containing it (as saved by
+    /// `query gateway fetch-proof`), to build the transactions without querying the MultisigProver
+    #[clap(long)]
+    execute_data: Option<String>,
+
+    #[clap(flatten)]
+    rotation_timing: RotationTimingArgs,
+}
+
+/// What to do when a rotation is built before the gateway's minimum rotation delay has elapsed.
+#[derive(Args, Debug)]
+pub(crate) struct RotationTimingArgs {
+    /// Wait until the minimum rotation delay has elapsed before building a rotation
+    #[clap(long)]
+    wait: bool,
+
+    /// Build a rotation even if the minimum rotation delay has not elapsed yet, e.g. for a
+    /// transaction that is signed offline and broadcast later
+    #[clap(long, conflicts_with = "wait")]
+    force: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -289,6 +443,9 @@ pub(crate) async fn build_transaction(
     command: Commands,
     config: &Config,
 ) -> eyre::Result<Vec<SerializableSolanaTransaction>> {
+    // The gas payment of a contract call is matched with the message it pays for by being part of
+    // the same transaction.
+    let single_transaction = matches!(command, Commands::CallContract(_));
     let instructions = match command {
         Commands::Init(init_args) => init(fee_payer, init_args, config).await?,
         Commands::CallContract(call_contract_args) => call_contract(fee_payer, call_contract_args)?,
@@ -303,12 +460,29 @@ pub(crate) async fn build_transaction(
         Commands::Execute(execute_args) => execute(fee_payer, execute_args, config)?,
     };
 
+    let transactions: Vec<Vec<Instruction>> = if single_transaction {
+        vec![instructions]
+    } else {
+        instructions
+            .into_iter()
+            .map(|instruction| vec![instruction])
+            .collect()
+    };
+
+    serializable_transactions(fee_payer, transactions, config)
+}
+
+/// Wrap each group of instructions into an unsigned transaction paid by `fee_payer`.
+pub(crate) fn serializable_transactions(
+    fee_payer: &Pubkey,
+    transactions: Vec<Vec<Instruction>>,
+    config: &Config,
+) -> eyre::Result<Vec<SerializableSolanaTransaction>> {
     let blockhash = fetch_latest_blockhash(&config.url)?;
-    let mut serializable_transactions = Vec::with_capacity(instructions.len());
+    let mut serializable_transactions = Vec::with_capacity(transactions.len());
 
-    for instruction in instructions {
-        let message =
-            SolanaMessage::new_with_blockhash(&[instruction], Some(fee_payer), &blockhash);
+    for instructions in transactions {
+        let message = SolanaMessage::new_with_blockhash(&instructions, Some(fee_payer), &blockhash);
         let transaction = SolanaTransaction::new_unsigned(message);
         let params = SolanaTransactionParams {
             fee_payer: fee_payer.to_string(),
@@ -316,6 +490,7 @@ pub(crate) async fn build_transaction(
             nonce_account: None,
             nonce_authority: None,
             blockhash_for_message: blockhash.to_string(),
+            pending_update: None,
         };
 
         let serializable_tx = SerializableSolanaTransaction::new(transaction, params);
@@ -399,31 +574,36 @@ async fn get_verifier_set(
                 serde_json::to_vec(&crate::multisig_prover_types::QueryMsg::CurrentVerifierSet)?,
             )
             .await?;
-        let mut signers = BTreeMap::new();
-
-        for signer in multisig_prover_response.verifier_set.signers.values() {
-            let pubkey: PublicKey = signer.pub_key.clone().try_into()?;
-            let weight = signer.weight.u128();
-            signers.insert(pubkey, weight);
-        }
+        prover_verifier_set(&multisig_prover_response.verifier_set)
+    }
+}
 
-        Ok(VerifierSet {
-            nonce: multisig_prover_response.verifier_set.created_at,
-            signers,
-            quorum: multisig_prover_response.verifier_set.threshold.u128(),
-        })
+/// Convert a MultisigProver verifier set into the representation the gateway hashes.
+fn prover_verifier_set(
+    verifier_set: &crate::multisig_prover_types::VerifierSet,
+) -> eyre::Result<VerifierSet> {
+    let mut signers = BTreeMap::new();
+    for signer in verifier_set.signers.values() {
+        let pubkey: PublicKey = signer.pub_key.clone().try_into()?;
+        signers.insert(pubkey, signer.weight.u128());
     }
+
+    Ok(VerifierSet {
+        nonce: verifier_set.created_at,
+        signers,
+        quorum: verifier_set.threshold.u128(),
+    })
 }
 
-fn construct_execute_data(
+pub(crate) fn construct_execute_data(
     signer_set: &SigningVerifierSet,
     payload: Payload,
     domain_separator: [u8; 32],
+    under_sign: bool,
 ) -> eyre::Result<ExecuteData> {
     let message_hash = hash_payload::<Hasher>(&domain_separator, payload.clone())?;
-    let signatures = signer_set
-        .signers
-        .iter()
+    let signatures = select_signers(signer_set, under_sign)?
+        .into_iter()
         .map(|signer| {
             let signing_key = SigningKey::from(&signer.secret);
             let (signature, recovery_id) = signing_key.sign_prehash_recoverable(&message_hash)?;
@@ -459,6 +639,114 @@ fn construct_execute_data(
     Ok(execute_data)
 }
 
+/// The signers to sign with, heaviest first: just enough of them to reach the quorum or, when
+/// `under_sign` is set, as many as possible while staying below it.
+fn select_signers(
+    signer_set: &SigningVerifierSet,
+    under_sign: bool,
+) -> eyre::Result<Vec<&LocalSigner>> {
+    let mut signers: Vec<&LocalSigner> = signer_set.signers.iter().collect();
+    signers.sort_by(|a, b| b.weight.cmp(&a.weight));
+
+    let mut selected = Vec::new();
+    let mut weight = 0_u128;
+    for signer in signers {
+        let next_weight = weight.saturating_add(signer.weight);
+        if under_sign {
+            if next_weight >= signer_set.quorum {
+                continue;
+            }
+        } else if weight >= signer_set.quorum {
+            break;
+        }
+        selected.push(signer);
+        weight = next_weight;
+    }
+
+    if under_sign && selected.is_empty() {
+        eyre::bail!("Cannot under-sign: every signer alone reaches the quorum");
+    }
+    if !under_sign && weight < signer_set.quorum {
+        eyre::bail!(
+            "The signers' total weight {weight} does not reach the quorum {}",
+            signer_set.quorum
+        );
+    }
+    println!(
+        "Signing with {} of {} signer(s), weight {weight} for a quorum of {}",
+        selected.len(),
+        signer_set.signers.len(),
+        signer_set.quorum
+    );
+
+    Ok(selected)
+}
+
+/// A weighted SignerSet with its secret keys, as read from a JSON or TOML file:
+///
+/// ```json
+/// { "quorum": 3, "signers": [{ "secret": "<hex or key file>", "weight": 2 }, ...] }
+/// ```
+///
+/// The quorum defaults to the total weight and each weight defaults to 1.
+#[derive(serde::Deserialize, Debug)]
+struct SignerSetFile {
+    #[serde(default)]
+    quorum: Option<u128>,
+    signers: Vec<SignerSetFileEntry>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct SignerSetFileEntry {
+    secret: String,
+    #[serde(default = "default_signer_weight")]
+    weight: u128,
+}
+
+const fn default_signer_weight() -> u128 {
+    1
+}
+
+fn load_signing_verifier_set(path: &Path, nonce: u64) -> eyre::Result<SigningVerifierSet> {
+    let file: SignerSetFile = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str(&std::fs::read_to_string(path)?)?
+    } else {
+        read_json_file_from_path(path)?
+    };
+    if file.signers.is_empty() {
+        eyre::bail!("No signers found in {}", path.display());
+    }
+
+    let signers = file
+        .signers
+        .iter()
+        .map(|entry| {
+            Ok(LocalSigner {
+                secret: utils::parse_secret_key(&entry.secret)?,
+                weight: entry.weight,
+            })
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    Ok(match file.quorum {
+        Some(quorum) => SigningVerifierSet::new_with_quorum(signers, nonce, quorum),
+        None => SigningVerifierSet::new(signers, nonce),
+    })
+}
+
+/// The SignerSet signing a proof, from a single `--signer` or a `--signers-file`.
+pub(crate) fn signing_verifier_set(
+    signer: Option<k256::SecretKey>,
+    signers_file: Option<&Path>,
+    nonce: u64,
+) -> eyre::Result<SigningVerifierSet> {
+    match (signer, signers_file) {
+        (Some(secret), _) => Ok(build_signing_verifier_set(secret, nonce)),
+        (None, Some(path)) => load_signing_verifier_set(path, nonce),
+        (None, None) => eyre::bail!("Either a signer or a signers file is required"),
+    }
+}
+
 fn build_signing_verifier_set(secret: k256::SecretKey, nonce: u64) -> SigningVerifierSet {
     let signer = LocalSigner {
         secret,
@@ -468,7 +756,7 @@ fn build_signing_verifier_set(secret: k256::SecretKey, nonce: u64) -> SigningVer
     SigningVerifierSet::new(vec![signer], nonce)
 }
 
-fn append_verification_flow_instructions(
+pub(crate) fn append_verification_flow_instructions(
     fee_payer: &Pubkey,
     instructions: &mut Vec<Instruction>,
     execute_data: &ExecuteData,
@@ -633,9 +921,13 @@ fn call_contract(
     let (event_authority_pda, _) =
         Pubkey::find_program_address(&[b"__event_authority"], &solana_axelar_gateway::id());
 
+    let payload_hash = solana_sdk::keccak::hash(&payload).to_bytes();
+    let destination_chain = call_contract_args.destination_chain;
+    let destination_address = call_contract_args.destination_address;
+
     let ix_data = solana_axelar_gateway::instruction::CallContract {
-        destination_chain: call_contract_args.destination_chain,
-        destination_contract_address: call_contract_args.destination_address,
+        destination_chain: destination_chain.clone(),
+        destination_contract_address: destination_address.clone(),
         payload,
         signing_pda_bump: 0,
     }
@@ -649,11 +941,152 @@ fn call_contract(
         AccountMeta::new_readonly(solana_axelar_gateway::id(), false),
     ];
 
-    Ok(vec![Instruction {
+    let mut instructions = Vec::with_capacity(2);
+    if let Some(amount) = call_contract_args.gas_amount {
+        instructions.push(pay_gas_instruction(
+            fee_payer,
+            &destination_chain,
+            &destination_address,
+            payload_hash,
+            amount,
+            call_contract_args.refund_address.unwrap_or(*fee_payer),
+        ));
+    }
+    instructions.push(Instruction {
         program_id: solana_axelar_gateway::id(),
         accounts,
         data: ix_data,
-    }])
+    });
+
+    Ok(instructions)
+}
+
+/// Instruction paying native gas to the AxelarGasService for the message of a contract call sent
+/// in the same transaction.
+fn pay_gas_instruction(
+    fee_payer: &Pubkey,
+    destination_chain: &str,
+    destination_address: &str,
+    payload_hash: [u8; 32],
+    amount: u64,
+    refund_address: Pubkey,
+) -> Instruction {
+    use anchor_lang::ToAccountMetas;
+
+    let (treasury_pda, _) = Pubkey::find_program_address(
+        &[solana_axelar_gas_service::state::Treasury::SEED_PREFIX],
+        &solana_axelar_gas_service::id(),
+    );
+    let (event_authority_pda, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &solana_axelar_gas_service::id());
+
+    let accounts = solana_axelar_gas_service::accounts::PayGas {
+        sender: *fee_payer,
+        treasury: treasury_pda,
+        system_program: solana_sdk_ids::system_program::ID,
+        program: solana_axelar_gas_service::id(),
+        event_authority: event_authority_pda,
+    }
+    .to_account_metas(None);
+
+    let ix_data = solana_axelar_gas_service::instruction::PayGas {
+        destination_chain: destination_chain.to_owned(),
+        destination_address: destination_address.to_owned(),
+        payload_hash,
+        amount,
+        refund_address,
+    }
+    .data();
+
+    Instruction {
+        program_id: solana_axelar_gas_service::id(),
+        accounts,
+        data: ix_data,
+    }
+}
+
+/// Whether the transaction calls a contract through the AxelarGateway.
+pub(crate) fn is_call_contract(transaction: &SolanaTransaction) -> bool {
+    use anchor_lang::Discriminator;
+
+    let message = &transaction.message;
+    message.instructions.iter().any(|compiled_ix| {
+        message
+            .account_keys
+            .get(usize::from(compiled_ix.program_id_index))
+            .is_some_and(|program_id| *program_id == solana_axelar_gateway::id())
+            && compiled_ix
+                .data
+                .starts_with(solana_axelar_gateway::instruction::CallContract::DISCRIMINATOR)
+    })
+}
+
+/// The message ids of the `CallContractEvent`s emitted by a transaction. A Solana message id is
+/// `<signature>-<instruction index>.<inner instruction index>`, locating the self-CPI that emitted
+/// the event, with the inner instruction index starting at 1.
+fn call_contract_message_ids(
+    rpc_client: &RpcClient,
+    signature: &SolanaSignature,
+) -> eyre::Result<Vec<(String, solana_axelar_gateway::CallContractEvent)>> {
+    use base64::Engine;
+    use solana_transaction_status::UiInstruction;
+
+    let transaction = rpc_client.get_transaction(signature, UiTransactionEncoding::Base64)?;
+    let meta = transaction
+        .transaction
+        .meta
+        .ok_or_else(|| eyre!("Transaction missing metadata"))?;
+
+    let mut message_ids = Vec::new();
+    for inner_ix_set in meta.inner_instructions.unwrap_or_else(std::vec::Vec::new) {
+        for (position, inner_ix) in inner_ix_set.instructions.iter().enumerate() {
+            let UiInstruction::Compiled(compiled_ix) = inner_ix else {
+                continue;
+            };
+            let Ok(data) = base64::engine::general_purpose::STANDARD.decode(&compiled_ix.data)
+            else {
+                continue;
+            };
+            if let Some(GatewayEvent::CallContract(event)) = parse_gateway_event(&data)? {
+                message_ids.push((
+                    format!(
+                        "{signature}-{}.{}",
+                        inner_ix_set.index,
+                        position.saturating_add(1)
+                    ),
+                    event,
+                ));
+            }
+        }
+    }
+
+    Ok(message_ids)
+}
+
+/// Print the message ids of the contract calls sent by a transaction along with their Axelarscan
+/// links.
+pub(crate) fn print_call_contract_messages(
+    rpc_client: &RpcClient,
+    config: &Config,
+    signature: &SolanaSignature,
+) -> eyre::Result<()> {
+    let chains_info: serde_json::Value = read_json_file_from_path(&config.chains_info_file)?;
+    let explorer_url = chains_info[AXELAR_KEY]["explorer"]["url"]
+        .as_str()
+        .map(|url| url.trim_end_matches('/').to_owned());
+
+    for (message_id, event) in call_contract_message_ids(rpc_client, signature)? {
+        println!(
+            "\u{1F4E8} Message {message_id} to {} ({})",
+            event.destination_chain, event.destination_contract_address
+        );
+        match &explorer_url {
+            Some(url) => println!("   Axelarscan: {url}/gmp/{message_id}"),
+            None => println!("   No Axelarscan URL found in the chains info file"),
+        }
+    }
+
+    Ok(())
 }
 
 fn transfer_operatorship(
@@ -688,7 +1121,11 @@ fn approve(
 ) -> eyre::Result<Vec<Instruction>> {
     let mut instructions = vec![];
     let chains_info: serde_json::Value = read_json_file_from_path(&config.chains_info_file)?;
-    let signer_set = build_signing_verifier_set(approve_args.signer.clone(), approve_args.nonce);
+    let signer_set = signing_verifier_set(
+        approve_args.signer.clone(),
+        approve_args.signers_file.as_deref(),
+        approve_args.nonce,
+    )?;
     let domain_separator = domain_separator(&chains_info, config.network_type, &config.chain)?;
     let payload_bytes = hex::decode(
         approve_args
@@ -709,7 +1146,12 @@ fn approve(
     };
     let gateway_config_pda = solana_axelar_gateway::GatewayConfig::find_pda().0;
     let gmp_payload = Payload::Messages(Messages(vec![message]));
-    let execute_data = construct_execute_data(&signer_set, gmp_payload, domain_separator)?;
+    let execute_data = construct_execute_data(
+        &signer_set,
+        gmp_payload,
+        domain_separator,
+        approve_args.under_sign,
+    )?;
     let verification_session_pda = append_verification_flow_instructions(
         fee_payer,
         &mut instructions,
@@ -779,14 +1221,77 @@ fn approve(
     Ok(instructions)
 }
 
+/// Check that the gateway accepts a rotation now, based on the cluster time, the last rotation and
+/// the minimum rotation delay. Rotations signed by the operator bypass the delay. Returns the
+/// operator when the fee payer is the operator, to be passed to the rotation.
+async fn ensure_rotation_allowed(
+    fee_payer: &Pubkey,
+    timing: &RotationTimingArgs,
+    config: &Config,
+) -> eyre::Result<Option<Pubkey>> {
+    use anchor_lang::AccountDeserialize;
+    use solana_axelar_gateway::state::config::GatewayConfig;
+
+    let rpc_client = RpcClient::new(config.url.clone());
+    let (gateway_config_pda, _) = solana_axelar_gateway::GatewayConfig::find_pda();
+
+    loop {
+        let gateway_config = GatewayConfig::try_deserialize(
+            &mut rpc_client.get_account_data(&gateway_config_pda)?.as_slice(),
+        )?;
+        if gateway_config.operator == *fee_payer {
+            println!(
+                "Rotation signed by the gateway operator, the minimum rotation delay does not apply"
+            );
+            return Ok(Some(*fee_payer));
+        }
+
+        let earliest = i64::try_from(gateway_config.last_rotation_timestamp)?
+            .saturating_add(i64::try_from(gateway_config.minimum_rotation_delay)?);
+        let clock_account = rpc_client.get_account(&solana_sdk::sysvar::clock::ID)?;
+        let (clock, _): (solana_sdk::clock::Clock, _) =
+            bincode::serde::decode_from_slice(&clock_account.data, bincode::config::legacy())?;
+        let remaining = earliest.saturating_sub(clock.unix_timestamp);
+
+        if remaining <= 0 {
+            println!(
+                "Rotation window open since {earliest} (cluster time {})",
+                clock.unix_timestamp
+            );
+            return Ok(None);
+        }
+        if timing.force {
+            println!(
+                "\u{26A0}\u{FE0F}  The gateway rejects rotations until {earliest} ({remaining} seconds from now), building it anyway"
+            );
+            return Ok(None);
+        }
+        if !timing.wait {
+            eyre::bail!(
+                "The gateway rejects rotations until {earliest} ({remaining} seconds from now, minimum rotation delay {} seconds). Use --wait to wait for it, or --force to build the rotation anyway",
+                gateway_config.minimum_rotation_delay
+            );
+        }
+
+        println!("Waiting {remaining} seconds for the rotation window to open...");
+        tokio::time::sleep(std::time::Duration::from_secs(remaining.unsigned_abs())).await;
+    }
+}
+
 async fn rotate(
     fee_payer: &Pubkey,
     rotate_args: RotateArgs,
     config: &Config,
 ) -> eyre::Result<Vec<Instruction>> {
+    let operator = ensure_rotation_allowed(fee_payer, &rotate_args.rotation_timing, config).await?;
+
     let mut instructions = vec![];
     let chains_info: serde_json::Value = read_json_file_from_path(&config.chains_info_file)?;
-    let signer_set = build_signing_verifier_set(rotate_args.signer, rotate_args.nonce);
+    let signer_set = signing_verifier_set(
+        rotate_args.signer,
+        rotate_args.signers_file.as_deref(),
+        rotate_args.nonce,
+    )?;
     let new_verifier_set = get_verifier_set(
         rotate_args.new_signer.as_ref(),
         rotate_args.new_signer_set.as_ref(),
@@ -799,13 +1304,14 @@ async fn rotate(
     let current_verifier_set_hash =
         verifier_set_hash::<Hasher>(&signer_set.verifier_set(), &domain_separator)?;
     let new_verifier_set_hash = verifier_set_hash::<Hasher>(&new_verifier_set, &domain_separator)?;
-    let (verifier_set_tracker_pda, _bump) =
-        solana_axelar_gateway::VerifierSetTracker::find_pda(&current_verifier_set_hash);
-    let (new_verifier_set_tracker_pda, _bump) =
-        solana_axelar_gateway::VerifierSetTracker::find_pda(&new_verifier_set_hash);
     let gateway_config_pda = solana_axelar_gateway::GatewayConfig::find_pda().0;
     let payload = Payload::NewVerifierSet(new_verifier_set.clone());
-    let execute_data = construct_execute_data(&signer_set, payload, domain_separator)?;
+    let execute_data = construct_execute_data(
+        &signer_set,
+        payload,
+        domain_separator,
+        rotate_args.under_sign,
+    )?;
     let verification_session_pda = append_verification_flow_instructions(
         fee_payer,
         &mut instructions,
@@ -813,105 +1319,225 @@ async fn rotate(
         &gateway_config_pda,
     )?;
 
-    let (event_authority_pda, _) =
-        Pubkey::find_program_address(&[b"__event_authority"], &solana_axelar_gateway::id());
-
-    let rotate_ix_data = solana_axelar_gateway::instruction::RotateSigners {
-        new_verifier_set_merkle_root: new_verifier_set_hash,
-    }
-    .data();
-
-    instructions.push(Instruction {
-        program_id: solana_axelar_gateway::id(),
-        accounts: vec![
-            AccountMeta::new(gateway_config_pda, false),
-            AccountMeta::new_readonly(verification_session_pda, false),
-            AccountMeta::new_readonly(verifier_set_tracker_pda, false),
-            AccountMeta::new(new_verifier_set_tracker_pda, false),
-            AccountMeta::new(*fee_payer, true),
-            AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
-            AccountMeta::new_readonly(event_authority_pda, false),
-            AccountMeta::new_readonly(solana_axelar_gateway::id(), false),
-        ],
-        data: rotate_ix_data,
-    });
+    instructions.push(rotate_signers_instruction(
+        fee_payer,
+        operator,
+        &gateway_config_pda,
+        &verification_session_pda,
+        &current_verifier_set_hash,
+        &new_verifier_set_hash,
+    ));
 
     Ok(instructions)
 }
 
-#[allow(clippy::too_many_lines)]
-async fn submit_proof(
+/// The `RotateSigners` instruction registering the verifier set with merkle root
+/// `new_verifier_set_merkle_root`, whose rotation was verified by the session. The rotation
+/// bypasses the minimum rotation delay when signed by the gateway `operator`.
+fn rotate_signers_instruction(
     fee_payer: &Pubkey,
-    submit_proof_args: SubmitProofArgs,
-    config: &Config,
-) -> eyre::Result<Vec<Instruction>> {
-    let chains_info: serde_json::Value = read_json_file_from_path(&config.chains_info_file)?;
-    let multisig_prover_address = {
-        let address = <String as serde::Deserialize>::deserialize(
-            &chains_info[AXELAR_KEY][CONTRACTS_KEY][MULTISIG_PROVER_KEY][&config.chain]
-                [ADDRESS_KEY],
-        )?;
+    operator: Option<Pubkey>,
+    gateway_config_pda: &Pubkey,
+    verification_session_pda: &Pubkey,
+    signing_verifier_set_merkle_root: &[u8; 32],
+    new_verifier_set_merkle_root: &[u8; 32],
+) -> Instruction {
+    use anchor_lang::ToAccountMetas;
 
-        cosmrs::AccountId::from_str(&address).unwrap()
-    };
-    let axelar_grpc_endpoint =
-        <String as serde::Deserialize>::deserialize(&chains_info[AXELAR_KEY][GRPC_KEY])?;
-    let multisig_prover_response = query_axelar::<crate::multisig_prover_types::ProofResponse>(
-        axelar_grpc_endpoint,
-        multisig_prover_address,
-        serde_json::to_vec(&crate::multisig_prover_types::QueryMsg::Proof {
-            multisig_session_id: submit_proof_args.multisig_session_id,
-        })?,
-    )
-    .await?;
+    let (verifier_set_tracker_pda, _bump) =
+        solana_axelar_gateway::VerifierSetTracker::find_pda(signing_verifier_set_merkle_root);
+    let (new_verifier_set_tracker_pda, _bump) =
+        solana_axelar_gateway::VerifierSetTracker::find_pda(new_verifier_set_merkle_root);
+    let (event_authority, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &solana_axelar_gateway::id());
 
-    let gateway_config_pda = solana_axelar_gateway::GatewayConfig::find_pda().0;
-    let execute_data: ExecuteData = match multisig_prover_response.status {
-        ProofStatus::Pending => eyre::bail!("Proof is not completed yet"),
-        ProofStatus::Completed { execute_data } => borsh::from_slice(execute_data.as_slice())?,
+    let accounts = solana_axelar_gateway::accounts::RotateSigners {
+        gateway_root_pda: *gateway_config_pda,
+        verification_session_account: *verification_session_pda,
+        verifier_set_tracker_pda,
+        new_verifier_set_tracker: new_verifier_set_tracker_pda,
+        payer: *fee_payer,
+        operator,
+        system_program: solana_sdk_ids::system_program::ID,
+        event_authority,
+        program: solana_axelar_gateway::id(),
     };
 
-    let mut instructions = Vec::new();
-    let verification_session_pda = append_verification_flow_instructions(
+    Instruction {
+        program_id: solana_axelar_gateway::id(),
+        accounts: accounts.to_account_metas(None),
+        data: solana_axelar_gateway::instruction::RotateSigners {
+            new_verifier_set_merkle_root: *new_verifier_set_merkle_root,
+        }
+        .data(),
+    }
+}
+
+/// The gRPC endpoint of the Axelar network and the address of the chain's MultisigProver.
+fn multisig_prover_target(
+    chains_info: &serde_json::Value,
+    chain: &str,
+) -> eyre::Result<(String, cosmrs::AccountId)> {
+    let address = <String as serde::Deserialize>::deserialize(
+        &chains_info[AXELAR_KEY][CONTRACTS_KEY][MULTISIG_PROVER_KEY][chain][ADDRESS_KEY],
+    )?;
+    let multisig_prover_address = cosmrs::AccountId::from_str(&address)
+        .map_err(|e| eyre!("Invalid MultisigProver address {address}: {e}"))?;
+    let axelar_grpc_endpoint =
+        <String as serde::Deserialize>::deserialize(&chains_info[AXELAR_KEY][GRPC_KEY])?;
+
+    Ok((axelar_grpc_endpoint, multisig_prover_address))
+}
+
+/// Query the chain's MultisigProver for the proof of a signing session.
+pub(crate) async fn query_proof(
+    chains_info: &serde_json::Value,
+    chain: &str,
+    multisig_session_id: u64,
+) -> eyre::Result<crate::multisig_prover_types::ProofResponse> {
+    let (axelar_grpc_endpoint, multisig_prover_address) =
+        multisig_prover_target(chains_info, chain)?;
+
+    query_axelar::<crate::multisig_prover_types::ProofResponse>(
+        axelar_grpc_endpoint,
+        multisig_prover_address,
+        serde_json::to_vec(&crate::multisig_prover_types::QueryMsg::Proof {
+            multisig_session_id,
+        })?,
+    )
+    .await
+}
+
+/// The `execute_data` of a completed proof, as returned by the MultisigProver.
+async fn completed_execute_data(
+    multisig_session_id: u64,
+    config: &Config,
+) -> eyre::Result<Vec<u8>> {
+    let chains_info: serde_json::Value = read_json_file_from_path(&config.chains_info_file)?;
+    let multisig_prover_response =
+        query_proof(&chains_info, &config.chain, multisig_session_id).await?;
+
+    match multisig_prover_response.status {
+        ProofStatus::Pending => eyre::bail!("Proof is not completed yet"),
+        ProofStatus::Completed { execute_data } => Ok(execute_data),
+    }
+}
+
+/// Decode `execute_data` given as a hex string or as the path of a file holding one.
+fn read_execute_data(source: &str) -> eyre::Result<Vec<u8>> {
+    let path = Path::new(source);
+    let contents = if path.is_file() {
+        std::fs::read_to_string(path)?
+    } else {
+        source.to_owned()
+    };
+    let hex_data = contents.trim();
+
+    hex::decode(hex_data.strip_prefix("0x").unwrap_or(hex_data)).map_err(|e| {
+        eyre!("Invalid execute_data, expected a hex string or a file holding one: {e}")
+    })
+}
+
+async fn fetch_proof(args: FetchProofArgs, config: &Config) -> eyre::Result<()> {
+    let execute_data = completed_execute_data(args.multisig_session_id, config).await?;
+    let decoded: ExecuteData = borsh::from_slice(&execute_data)
+        .map_err(|e| eyre!("Failed to decode execute_data: {e}"))?;
+    let output = args.output.unwrap_or_else(|| {
+        config
+            .output_dir
+            .join(format!("execute-data-{}.hex", args.multisig_session_id))
+    });
+
+    std::fs::write(&output, hex::encode(&execute_data))?;
+
+    let payload = match &decoded.payload_items {
+        MerklizedPayload::NewMessages { messages } => format!("{} message(s)", messages.len()),
+        MerklizedPayload::VerifierSetRotation { .. } => "verifier set rotation".to_owned(),
+    };
+    println!("------------------------------------------");
+    println!("Proof of session {}: {payload}", args.multisig_session_id);
+    println!(
+        "   Payload merkle root: 0x{}",
+        hex::encode(decoded.payload_merkle_root)
+    );
+    println!(
+        "   Signing verifier set: 0x{}",
+        hex::encode(decoded.signing_verifier_set_merkle_root)
+    );
+    println!("   Saved to {}", output.display());
+    println!("------------------------------------------");
+
+    Ok(())
+}
+
+async fn submit_proof(
+    fee_payer: &Pubkey,
+    submit_proof_args: SubmitProofArgs,
+    config: &Config,
+) -> eyre::Result<Vec<Instruction>> {
+    let encoded_execute_data = match (
+        submit_proof_args.multisig_session_id,
+        submit_proof_args.execute_data,
+    ) {
+        (_, Some(execute_data)) => read_execute_data(&execute_data)?,
+        (Some(multisig_session_id), None) => {
+            completed_execute_data(multisig_session_id, config).await?
+        }
+        (None, None) => eyre::bail!("Either --multisig-session-id or --execute-data is required"),
+    };
+
+    let gateway_config_pda = solana_axelar_gateway::GatewayConfig::find_pda().0;
+    let execute_data: ExecuteData = borsh::from_slice(&encoded_execute_data)
+        .map_err(|e| eyre!("Failed to decode execute_data: {e}"))?;
+    let operator = if let MerklizedPayload::VerifierSetRotation { .. } = execute_data.payload_items
+    {
+        ensure_rotation_allowed(fee_payer, &submit_proof_args.rotation_timing, config).await?
+    } else {
+        None
+    };
+
+    let mut instructions = Vec::new();
+    let verification_session_pda = append_verification_flow_instructions(
         fee_payer,
         &mut instructions,
         &execute_data,
         &gateway_config_pda,
     )?;
+    append_payload_instructions(
+        fee_payer,
+        operator,
+        &mut instructions,
+        &execute_data,
+        &verification_session_pda,
+        &gateway_config_pda,
+    );
+
+    Ok(instructions)
+}
 
-    match execute_data.payload_items {
+/// Append the instructions acting on a verified payload: the signer rotation or the approval of
+/// each message. `operator` is passed to the rotation, see `rotate_signers_instruction`.
+pub(crate) fn append_payload_instructions(
+    fee_payer: &Pubkey,
+    operator: Option<Pubkey>,
+    instructions: &mut Vec<Instruction>,
+    execute_data: &ExecuteData,
+    verification_session_pda: &Pubkey,
+    gateway_config_pda: &Pubkey,
+) {
+    match &execute_data.payload_items {
         MerklizedPayload::VerifierSetRotation {
             new_verifier_set_merkle_root,
         } => {
             println!("Building instruction to rotate signers");
-            let (verifier_set_tracker_pda, _bump) =
-                solana_axelar_gateway::VerifierSetTracker::find_pda(
-                    &execute_data.signing_verifier_set_merkle_root,
-                );
-            let (new_verifier_set_tracker_pda, _bump) =
-                solana_axelar_gateway::VerifierSetTracker::find_pda(&new_verifier_set_merkle_root);
-            let (event_authority_pda, _) =
-                Pubkey::find_program_address(&[b"__event_authority"], &solana_axelar_gateway::id());
-
-            let rotate_ix_data = solana_axelar_gateway::instruction::RotateSigners {
+            instructions.push(rotate_signers_instruction(
+                fee_payer,
+                operator,
+                gateway_config_pda,
+                verification_session_pda,
+                &execute_data.signing_verifier_set_merkle_root,
                 new_verifier_set_merkle_root,
-            }
-            .data();
-
-            instructions.push(Instruction {
-                program_id: solana_axelar_gateway::id(),
-                accounts: vec![
-                    AccountMeta::new(gateway_config_pda, false),
-                    AccountMeta::new_readonly(verification_session_pda, false),
-                    AccountMeta::new_readonly(verifier_set_tracker_pda, false),
-                    AccountMeta::new(new_verifier_set_tracker_pda, false),
-                    AccountMeta::new(*fee_payer, true),
-                    AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
-                    AccountMeta::new_readonly(event_authority_pda, false),
-                    AccountMeta::new_readonly(solana_axelar_gateway::id(), false),
-                ],
-                data: rotate_ix_data,
-            });
+            ));
         }
         MerklizedPayload::NewMessages { messages } => {
             for message in messages {
@@ -959,9 +1585,9 @@ async fn submit_proof(
                 instructions.push(Instruction {
                     program_id: solana_axelar_gateway::id(),
                     accounts: vec![
-                        AccountMeta::new_readonly(gateway_config_pda, false),
+                        AccountMeta::new_readonly(*gateway_config_pda, false),
                         AccountMeta::new(*fee_payer, true),
-                        AccountMeta::new_readonly(verification_session_pda, false),
+                        AccountMeta::new_readonly(*verification_session_pda, false),
                         AccountMeta::new(incoming_message_pda, false),
                         AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
                         AccountMeta::new_readonly(event_authority_pda, false),
@@ -972,12 +1598,10 @@ async fn submit_proof(
             }
         }
     }
-
-    Ok(instructions)
 }
 
 fn execute(
-    _fee_payer: &Pubkey,
+    fee_payer: &Pubkey,
     execute_args: ExecuteArgs,
     config: &Config,
 ) -> eyre::Result<Vec<Instruction>> {
@@ -999,8 +1623,18 @@ fn execute(
         payload_hash: solana_sdk::keccak::hashv(&[&payload]).to_bytes(),
     };
 
+    execute_message(fee_payer, message, payload, config)
+}
+
+/// Build the instructions executing an approved message on its destination program.
+pub(crate) fn execute_message(
+    fee_payer: &Pubkey,
+    message: Message,
+    payload: Vec<u8>,
+    config: &Config,
+) -> eyre::Result<Vec<Instruction>> {
     let command_id = command_id(&message.cc_id.chain, &message.cc_id.id);
-    let (_incoming_message_pda, _) = solana_axelar_gateway::IncomingMessage::find_pda(&command_id);
+    let (incoming_message_pda, _) = solana_axelar_gateway::IncomingMessage::find_pda(&command_id);
 
     let destination_address = Pubkey::from_str(&message.destination_address).map_err(|e| {
         eyre::eyre!(
@@ -1010,17 +1644,161 @@ fn execute(
         )
     })?;
 
+    ensure_message_approved(config, &incoming_message_pda, &message.payload_hash)?;
+
     if destination_address == solana_axelar_its::id() {
-        eyre::bail!("ITS GMP execution not yet implemented.");
+        return crate::its::execute_instructions(
+            fee_payer,
+            &command_id,
+            &incoming_message_pda,
+            &message,
+            &payload,
+            config,
+        );
     } else if destination_address == solana_axelar_governance::id() {
-        eyre::bail!(
-            "Governance GMP execution not yet implemented for new Anchor program. Use governance-specific commands instead."
+        return crate::governance::process_gmp_instructions(
+            fee_payer,
+            &command_id,
+            &incoming_message_pda,
+            &message,
+            &payload,
         );
-    } else {
+    }
+
+    executable_instruction(
+        &destination_address,
+        &command_id,
+        &incoming_message_pda,
+        message,
+        payload,
+    )
+}
+
+/// The `IncomingMessage` stored at `incoming_message_pda`, or `None` if the message has not been
+/// approved.
+fn fetch_incoming_message(
+    rpc_client: &RpcClient,
+    incoming_message_pda: &Pubkey,
+) -> eyre::Result<Option<solana_axelar_gateway::IncomingMessage>> {
+    use anchor_lang::AccountDeserialize;
+
+    let Some(account) = rpc_client
+        .get_account_with_commitment(incoming_message_pda, rpc_client.commitment())?
+        .value
+    else {
+        return Ok(None);
+    };
+    let incoming_message = solana_axelar_gateway::IncomingMessage::try_deserialize(
+        &mut account.data.get(8..).unwrap_or_default(),
+    )
+    .map_err(|_| eyre!("Failed to deserialize message data"))?;
+
+    Ok(Some(incoming_message))
+}
+
+/// Check that the message was approved by the gateway with the payload we are about to execute.
+fn ensure_message_approved(
+    config: &Config,
+    incoming_message_pda: &Pubkey,
+    payload_hash: &[u8; 32],
+) -> eyre::Result<()> {
+    let rpc_client = RpcClient::new(config.url.clone());
+    let incoming_message =
+        fetch_incoming_message(&rpc_client, incoming_message_pda)?.ok_or_else(|| {
+            eyre!(
+                "Message not found on the gateway ({incoming_message_pda}). Has it been approved?"
+            )
+        })?;
+
+    if !incoming_message.status.is_approved() {
+        eyre::bail!("Message has already been executed");
+    }
+    if incoming_message.payload_hash != *payload_hash {
         eyre::bail!(
-            "Generic executable instruction building not yet implemented for v2. Use ITS or Governance specific commands."
+            "Payload hash mismatch: the gateway approved 0x{}, the provided payload hashes to 0x{}",
+            hex::encode(incoming_message.payload_hash),
+            hex::encode(payload_hash)
+        );
+    }
+
+    Ok(())
+}
+
+/// PDA of `destination` through which it validates the message identified by `command_id`.
+pub(crate) fn signing_pda(destination: &Pubkey, command_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            solana_axelar_gateway::seed_prefixes::VALIDATE_MESSAGE_SIGNING_SEED,
+            command_id,
+        ],
+        destination,
+    )
+}
+
+/// Accounts through which a program implementing the Axelar executable interface validates the
+/// message with the gateway. They come first in its `execute` instruction.
+pub(crate) fn executable_accounts(
+    destination: &Pubkey,
+    command_id: &[u8; 32],
+    incoming_message_pda: &Pubkey,
+) -> Vec<AccountMeta> {
+    let (signing_pda, _) = signing_pda(destination, command_id);
+    let gateway_config_pda = solana_axelar_gateway::GatewayConfig::find_pda().0;
+    let (event_authority_pda, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &solana_axelar_gateway::id());
+
+    vec![
+        AccountMeta::new(*incoming_message_pda, false),
+        AccountMeta::new_readonly(signing_pda, false),
+        AccountMeta::new_readonly(gateway_config_pda, false),
+        AccountMeta::new_readonly(event_authority_pda, false),
+        AccountMeta::new_readonly(solana_axelar_gateway::id(), false),
+    ]
+}
+
+/// Data of the Axelar executable `execute(message: Message, payload: Vec<u8>)` instruction.
+pub(crate) fn execute_instruction_data(message: Message, payload: Vec<u8>) -> Vec<u8> {
+    solana_axelar_gateway::executable::ExecuteInstruction { message, payload }.data()
+}
+
+/// Build the `execute` instruction of a program implementing the Axelar executable interface: the
+/// accounts validating the message with the gateway, followed by the accounts encoded in the
+/// `AxelarMessagePayload` (as built by `misc build-axelar-message`).
+fn executable_instruction(
+    destination: &Pubkey,
+    command_id: &[u8; 32],
+    incoming_message_pda: &Pubkey,
+    message: Message,
+    payload: Vec<u8>,
+) -> eyre::Result<Vec<Instruction>> {
+    let decoded = solana_axelar_gateway::payload::AxelarMessagePayload::decode(&payload)
+        .map_err(|e| eyre!("Failed to decode the payload as an AxelarMessagePayload: {e:?}"))?;
+
+    let mut accounts = executable_accounts(destination, command_id, incoming_message_pda);
+    let payload_accounts = decoded.account_meta();
+    accounts.extend(payload_accounts.iter().cloned());
+
+    println!("------------------------------------------");
+    println!(
+        "Executing message {} from {}",
+        message.cc_id.id, message.cc_id.chain
+    );
+    println!("   Destination program: {destination}");
+    println!("   Incoming message PDA: {incoming_message_pda}");
+    println!("   Payload accounts: {}", payload_accounts.len());
+    for meta in &payload_accounts {
+        println!(
+            "      {} (signer: {}, writable: {})",
+            meta.pubkey, meta.is_signer, meta.is_writable
         );
     }
+    println!("------------------------------------------");
+
+    Ok(vec![Instruction {
+        program_id: *destination,
+        accounts,
+        data: execute_instruction_data(message, payload),
+    }])
 }
 
 pub(crate) async fn query(command: QueryCommands, config: &Config) -> eyre::Result<()> {
@@ -1030,6 +1808,12 @@ pub(crate) async fn query(command: QueryCommands, config: &Config) -> eyre::Resu
         QueryCommands::Config => gateway_config(config),
         QueryCommands::VerifierSetTracker(args) => verifier_set_tracker(args, config),
         QueryCommands::ComputeMerkleRoot => compute_merkle_root(config).await,
+        QueryCommands::VerifierSet(VerifierSetCommands::Status) => {
+            verifier_set_status(config).await
+        }
+        QueryCommands::VerifierSetTrackers => verifier_set_trackers(config),
+        QueryCommands::VerificationSessions(args) => verification_sessions(&args, config),
+        QueryCommands::FetchProof(args) => fetch_proof(args, config).await,
     }
 }
 
@@ -1102,8 +1886,6 @@ fn verifier_set_tracker(args: VerifierSetTrackerArgs, config: &Config) -> eyre::
 }
 
 async fn compute_merkle_root(config: &Config) -> eyre::Result<()> {
-    use std::collections::BTreeMap;
-
     let chains_info: serde_json::Value =
         crate::utils::read_json_file_from_path(&config.chains_info_file)?;
 
@@ -1130,18 +1912,7 @@ async fn compute_merkle_root(config: &Config) -> eyre::Result<()> {
         )
         .await?;
 
-    let mut signers = BTreeMap::new();
-    for signer in multisig_prover_response.verifier_set.signers.values() {
-        let pubkey: PublicKey = signer.pub_key.clone().try_into()?;
-        let weight = signer.weight.u128();
-        signers.insert(pubkey, weight);
-    }
-
-    let verifier_set = VerifierSet {
-        nonce: multisig_prover_response.verifier_set.created_at,
-        signers,
-        quorum: multisig_prover_response.verifier_set.threshold.u128(),
-    };
+    let verifier_set = prover_verifier_set(&multisig_prover_response.verifier_set)?;
 
     println!("\nVerifier Set from Prover:");
     println!("  Nonce (created_at): {}", verifier_set.nonce);
@@ -1168,10 +1939,399 @@ async fn compute_merkle_root(config: &Config) -> eyre::Result<()> {
     Ok(())
 }
 
+/// The gateway accounts starting with the Anchor discriminator of an account type.
+fn fetch_gateway_accounts(
+    rpc_client: &RpcClient,
+    discriminator: &[u8],
+) -> eyre::Result<Vec<(Pubkey, solana_sdk::account::Account)>> {
+    use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+    use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+
+    Ok(rpc_client.get_program_accounts_with_config(
+        &solana_axelar_gateway::id(),
+        RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                discriminator.to_vec(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(solana_account_decoder_client_types::UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        },
+    )?)
+}
+
+/// All verifier set trackers registered with the gateway, with their addresses.
+fn fetch_verifier_set_trackers(
+    rpc_client: &RpcClient,
+) -> eyre::Result<
+    Vec<(
+        Pubkey,
+        solana_axelar_gateway::state::verifier_set_tracker::VerifierSetTracker,
+    )>,
+> {
+    use anchor_lang::{AccountDeserialize, Discriminator};
+    use solana_axelar_gateway::state::verifier_set_tracker::VerifierSetTracker;
+
+    fetch_gateway_accounts(rpc_client, VerifierSetTracker::DISCRIMINATOR)?
+        .into_iter()
+        .map(|(address, account)| {
+            Ok((
+                address,
+                VerifierSetTracker::try_deserialize(&mut account.data.as_slice())?,
+            ))
+        })
+        .collect()
+}
+
+/// How a verifier set registered at `epoch` stands against the gateway's retention of previous
+/// verifier sets.
+fn tracker_retention(
+    gateway_config: &solana_axelar_gateway::state::config::GatewayConfig,
+    epoch: U256,
+) -> String {
+    match gateway_config.current_epoch.checked_sub(epoch) {
+        Some(age) if age == U256::from(0_u64) => "current".to_owned(),
+        Some(age) if age > gateway_config.previous_verifier_set_retention => "expired".to_owned(),
+        Some(age) if age == gateway_config.previous_verifier_set_retention => {
+            "valid, expires at the next rotation".to_owned()
+        }
+        Some(age) => format!(
+            "valid for {} more rotation(s)",
+            gateway_config
+                .previous_verifier_set_retention
+                .checked_sub(age)
+                .unwrap_or_default()
+        ),
+        None => "newer than the current epoch".to_owned(),
+    }
+}
+
+fn fetch_gateway_config(
+    rpc_client: &RpcClient,
+) -> eyre::Result<solana_axelar_gateway::state::config::GatewayConfig> {
+    use anchor_lang::AccountDeserialize;
+    use solana_axelar_gateway::state::config::GatewayConfig;
+
+    let (gateway_config_pda, _) = solana_axelar_gateway::GatewayConfig::find_pda();
+    Ok(GatewayConfig::try_deserialize(
+        &mut rpc_client.get_account_data(&gateway_config_pda)?.as_slice(),
+    )?)
+}
+
+fn verifier_set_trackers(config: &Config) -> eyre::Result<()> {
+    let rpc_client = RpcClient::new(config.url.clone());
+    let gateway_config = fetch_gateway_config(&rpc_client)?;
+    let mut trackers = fetch_verifier_set_trackers(&rpc_client)?;
+    trackers.sort_by(|(_, a), (_, b)| b.epoch.cmp(&a.epoch));
+
+    println!("------------------------------------------");
+    println!(
+        "Current epoch: {} (previous verifier set retention: {})",
+        gateway_config.current_epoch, gateway_config.previous_verifier_set_retention
+    );
+    for (address, tracker) in &trackers {
+        println!("Verifier set tracker {address}:");
+        println!(
+            "   Merkle root: 0x{}",
+            hex::encode(tracker.verifier_set_hash)
+        );
+        println!("   Epoch: {}", tracker.epoch);
+        println!(
+            "   Status: {}",
+            tracker_retention(&gateway_config, tracker.epoch)
+        );
+    }
+    println!("------------------------------------------");
+    println!("{} verifier set tracker(s)", trackers.len());
+
+    Ok(())
+}
+
+/// The payload a verification session verifies and the quorum it needs, which the session account
+/// doesn't store, recovered from the transactions that initialized it and verified its first
+/// signature.
+#[derive(Default)]
+struct SessionOrigin {
+    payload: Option<([u8; 32], PayloadType)>,
+    quorum: Option<u128>,
+}
+
+/// Top-level instructions of a confirmed transaction.
+fn fetch_transaction_instructions(
+    rpc_client: &RpcClient,
+    signature: &SolanaSignature,
+) -> eyre::Result<Vec<Instruction>> {
+    let transaction = rpc_client
+        .get_transaction(signature, UiTransactionEncoding::Base64)?
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| eyre!("Failed to decode transaction {signature}"))?;
+    let message = &transaction.message;
+    let account_keys = message.static_account_keys();
+
+    message
+        .instructions()
+        .iter()
+        .map(|compiled_ix| {
+            let key = |index: u8| {
+                account_keys
+                    .get(usize::from(index))
+                    .copied()
+                    .ok_or_else(|| eyre!("Transaction {signature} uses address lookup tables"))
+            };
+            Ok(Instruction {
+                program_id: key(compiled_ix.program_id_index)?,
+                accounts: compiled_ix
+                    .accounts
+                    .iter()
+                    .map(|index| Ok(AccountMeta::new_readonly(key(*index)?, false)))
+                    .collect::<eyre::Result<_>>()?,
+                data: compiled_ix.data.clone(),
+            })
+        })
+        .collect()
+}
+
+fn session_origin(rpc_client: &RpcClient, session: &Pubkey) -> eyre::Result<SessionOrigin> {
+    use anchor_lang::{AnchorDeserialize, Discriminator};
+    use solana_axelar_gateway::instruction::InitializePayloadVerificationSession;
+    use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+    use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+
+    // Signatures are returned newest first, a page at a time: the whole history is needed to walk
+    // it from the initialization.
+    let mut history: Vec<RpcConfirmedTransactionStatusWithSignature> = Vec::new();
+    loop {
+        let page = rpc_client.get_signatures_for_address_with_config(
+            session,
+            GetConfirmedSignaturesForAddress2Config {
+                before: history
+                    .last()
+                    .map(|status| SolanaSignature::from_str(&status.signature))
+                    .transpose()?,
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+        let complete = page.len() < crate::events::SIGNATURES_PAGE_SIZE;
+        history.extend(page);
+        if complete {
+            break;
+        }
+    }
+
+    // Failed transactions, such as a verification of an already verified signature, don't tell
+    // anything about the session.
+    let mut origin = SessionOrigin::default();
+    for status in history.iter().rev().filter(|status| status.err.is_none()) {
+        let signature = SolanaSignature::from_str(&status.signature)?;
+        for instruction in fetch_transaction_instructions(rpc_client, &signature)? {
+            if let Some(verification) =
+                crate::verification::SignatureVerification::parse(&instruction)
+            {
+                origin.quorum.get_or_insert(verification.quorum());
+            } else if crate::verification::initialized_session(&instruction) == Some(*session) {
+                let args = InitializePayloadVerificationSession::try_from_slice(
+                    &instruction.data[InitializePayloadVerificationSession::DISCRIMINATOR.len()..],
+                )?;
+                origin.payload = Some((args.merkle_root, args.payload_type));
+            }
+        }
+        if origin.payload.is_some() && origin.quorum.is_some() {
+            break;
+        }
+    }
+
+    Ok(origin)
+}
+
+fn verification_sessions(args: &VerificationSessionsArgs, config: &Config) -> eyre::Result<()> {
+    use anchor_lang::Discriminator;
+    use solana_axelar_gateway::SignatureVerificationSessionData;
+
+    let rpc_client = RpcClient::new(config.url.clone());
+    let trackers = fetch_verifier_set_trackers(&rpc_client)?;
+    let sessions =
+        fetch_gateway_accounts(&rpc_client, SignatureVerificationSessionData::DISCRIMINATOR)?;
+
+    let mut listed = 0_usize;
+    let mut pending = 0_usize;
+    println!("------------------------------------------");
+    for (address, account) in &sessions {
+        let progress = crate::verification::SessionProgress::parse(address, &account.data)?;
+        let origin = session_origin(&rpc_client, address)?;
+        let reached = origin
+            .quorum
+            .is_some_and(|quorum| progress.accumulated_threshold >= quorum);
+        if !reached {
+            pending = pending.saturating_add(1);
+        }
+        if args.pending && reached {
+            continue;
+        }
+        listed = listed.saturating_add(1);
+
+        println!("Verification session {address}:");
+        match &origin.payload {
+            Some((root, payload_type)) => {
+                println!("   Payload: {payload_type:?} 0x{}", hex::encode(root));
+            }
+            None => println!("   Payload: unknown, initialization not found in the history"),
+        }
+        let signing_verifier_set = hex::encode(progress.signing_verifier_set_hash);
+        match trackers
+            .iter()
+            .find(|(_, tracker)| tracker.verifier_set_hash == progress.signing_verifier_set_hash)
+        {
+            Some((_, tracker)) => println!(
+                "   Signing verifier set: 0x{signing_verifier_set} (epoch {})",
+                tracker.epoch
+            ),
+            None => println!("   Signing verifier set: 0x{signing_verifier_set} (no tracker)"),
+        }
+        let quorum = origin
+            .quorum
+            .map_or_else(|| "unknown".to_owned(), |quorum| quorum.to_string());
+        println!(
+            "   Signatures verified: {} (weight {}/{quorum}){}",
+            progress.verified_signatures(),
+            progress.accumulated_threshold,
+            if reached { ", quorum reached" } else { "" }
+        );
+    }
+    println!("------------------------------------------");
+    println!(
+        "{listed} session(s) listed, {pending} of {} without quorum",
+        sessions.len()
+    );
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_lines)]
+async fn verifier_set_status(config: &Config) -> eyre::Result<()> {
+    let chains_info: serde_json::Value = read_json_file_from_path(&config.chains_info_file)?;
+    let (axelar_grpc_endpoint, multisig_prover_address) =
+        multisig_prover_target(&chains_info, &config.chain)?;
+    let domain_separator = domain_separator(&chains_info, config.network_type, &config.chain)?;
+
+    let current = query_axelar::<crate::multisig_prover_types::VerifierSetResponse>(
+        axelar_grpc_endpoint.clone(),
+        multisig_prover_address.clone(),
+        serde_json::to_vec(&crate::multisig_prover_types::QueryMsg::CurrentVerifierSet)?,
+    )
+    .await?;
+    let next = query_axelar::<Option<crate::multisig_prover_types::VerifierSetResponse>>(
+        axelar_grpc_endpoint,
+        multisig_prover_address,
+        serde_json::to_vec(&crate::multisig_prover_types::QueryMsg::NextVerifierSet)?,
+    )
+    .await?;
+
+    let current_root = verifier_set_hash::<Hasher>(
+        &prover_verifier_set(&current.verifier_set)?,
+        &domain_separator,
+    )?;
+    let next_root = next
+        .map(|next| {
+            verifier_set_hash::<Hasher>(
+                &prover_verifier_set(&next.verifier_set)?,
+                &domain_separator,
+            )
+        })
+        .transpose()?;
+
+    let rpc_client = RpcClient::new(config.url.clone());
+    let gateway_config = fetch_gateway_config(&rpc_client)?;
+    let mut trackers = fetch_verifier_set_trackers(&rpc_client)?;
+    trackers.sort_by(|(_, a), (_, b)| b.epoch.cmp(&a.epoch));
+
+    let tracker_epoch = |root: &[u8; 32]| {
+        trackers
+            .iter()
+            .find(|(_, tracker)| tracker.verifier_set_hash == *root)
+            .map(|(_, tracker)| tracker.epoch)
+    };
+    let describe = |root: &[u8; 32]| {
+        tracker_epoch(root).map_or_else(
+            || "not registered on Solana".to_owned(),
+            |epoch| format!("registered at epoch {epoch}"),
+        )
+    };
+
+    println!("------------------------------------------");
+    println!("Gateway:");
+    println!("   Current epoch: {}", gateway_config.current_epoch);
+    println!(
+        "   Previous verifier set retention: {}",
+        gateway_config.previous_verifier_set_retention
+    );
+    println!(
+        "   Last rotation: {} (minimum delay {} seconds)",
+        gateway_config.last_rotation_timestamp, gateway_config.minimum_rotation_delay
+    );
+    println!("MultisigProver:");
+    println!(
+        "   Current verifier set: 0x{} ({})",
+        hex::encode(current_root),
+        describe(&current_root)
+    );
+    match &next_root {
+        Some(root) => println!(
+            "   Next verifier set: 0x{} ({})",
+            hex::encode(root),
+            describe(root)
+        ),
+        None => println!("   Next verifier set: none"),
+    }
+
+    println!("Verifier set trackers on Solana:");
+    for (_, tracker) in &trackers {
+        println!(
+            "   epoch {}: 0x{} ({})",
+            tracker.epoch,
+            hex::encode(tracker.verifier_set_hash),
+            tracker_retention(&gateway_config, tracker.epoch)
+        );
+    }
+
+    println!("------------------------------------------");
+    let current_epoch = tracker_epoch(&current_root);
+    match (
+        current_epoch,
+        next_root.as_ref().map(|root| tracker_epoch(root)),
+    ) {
+        (None, _) => println!(
+            "\u{274c} Behind: the MultisigProver's current verifier set is not registered on Solana, a rotation is pending"
+        ),
+        (Some(_), Some(None)) => println!(
+            "\u{23f3} Rotation in progress: the MultisigProver's next verifier set is not registered on Solana yet"
+        ),
+        (Some(epoch), Some(Some(_))) => println!(
+            "\u{23f3} Solana already rotated to the MultisigProver's next verifier set (current set at epoch {epoch}); the prover has not confirmed the rotation yet"
+        ),
+        (Some(epoch), None) if epoch == gateway_config.current_epoch => {
+            println!(
+                "\u{2705} In sync: the gateway's current verifier set is the MultisigProver's"
+            );
+        }
+        (Some(epoch), None) => println!(
+            "\u{26A0}\u{FE0F}  The MultisigProver's current verifier set is registered at epoch {epoch}, but the gateway is at epoch {}",
+            gateway_config.current_epoch
+        ),
+    }
+
+    Ok(())
+}
+
 fn events(args: EventsArgs, config: &Config) -> eyre::Result<()> {
     let rpc_client = RpcClient::new(config.url.clone());
     let signature = SolanaSignature::from_str(&args.signature)?;
     let transaction = rpc_client.get_transaction(&signature, UiTransactionEncoding::Base64)?;
+    let account_keys = crate::events::transaction_account_keys(&transaction.transaction)?;
 
     let meta = transaction
         .transaction
@@ -1183,30 +2343,8 @@ fn events(args: EventsArgs, config: &Config) -> eyre::Result<()> {
     let mut event_count = 0;
 
     for (invocation_index, inner_ix_set) in inner_instructions.iter().enumerate() {
-        let invocation_events = inner_ix_set
-            .instructions
-            .iter()
-            .filter_map(|inner_ix| {
-                let data = match inner_ix {
-                    UiInstruction::Compiled(compiled_ix) => {
-                        match base64::engine::general_purpose::STANDARD.decode(&compiled_ix.data) {
-                            Ok(d) => d,
-                            Err(_) => return None,
-                        }
-                    }
-                    UiInstruction::Parsed(_) => return None,
-                };
-
-                match parse_gateway_event(&data) {
-                    Ok(Some(event)) => Some(event),
-                    Ok(None) => None,
-                    Err(e) => {
-                        eprintln!("\u{26A0}\u{FE0F}  Warning: {e}");
-                        None
-                    }
-                }
-            })
-            .collect::<Vec<_>>();
+        let invocation_events =
+            crate::events::decode_events(&account_keys, &inner_ix_set.instructions);
 
         if !invocation_events.is_empty() {
             println!("\u{2728} Invocation index [{invocation_index}]: ");
@@ -1229,14 +2367,14 @@ fn events(args: EventsArgs, config: &Config) -> eyre::Result<()> {
     }
 
     if event_count == 0 {
-        println!("\u{1F4EA} No gateway events found");
+        println!("\u{1F4EA} No Axelar events found");
     }
 
     Ok(())
 }
 
 #[allow(clippy::missing_asserts_for_indexing)]
-fn parse_gateway_event(data: &[u8]) -> eyre::Result<Option<GatewayEvent>> {
+pub(crate) fn parse_gateway_event(data: &[u8]) -> eyre::Result<Option<GatewayEvent>> {
     use anchor_lang::AnchorDeserialize;
     use anchor_lang::Discriminator;
     use solana_axelar_gateway::{
@@ -1287,32 +2425,286 @@ fn parse_gateway_event(data: &[u8]) -> eyre::Result<Option<GatewayEvent>> {
 }
 
 fn message_status(args: MessageStatusArgs, config: &Config) -> eyre::Result<()> {
-    use anchor_lang::AccountDeserialize;
-
     let rpc_client = RpcClient::new(config.url.clone());
-    let command_id = solana_sdk::keccak::hashv(&[
-        args.source_chain.as_bytes(),
-        b"-",
-        args.message_id.as_bytes(),
-    ])
-    .to_bytes();
-    let (incoming_message_pda, _) = solana_axelar_gateway::IncomingMessage::find_pda(&command_id);
-    let raw_incoming_message =
-        rpc_client
-            .get_account_data(&incoming_message_pda)
-            .map_err(|_| {
-                eyre!("Couldn't fetch information about given message. Are the details correct?")
-            })?;
-    let incoming_message =
-        solana_axelar_gateway::IncomingMessage::try_deserialize(&mut &raw_incoming_message[8..])
-            .map_err(|_| eyre!("Failed to deserialize message data"))?;
 
-    let status = if incoming_message.status.is_approved() {
-        String::from("Approved")
-    } else {
-        String::from("Executed")
+    let (Some(source_chain), Some(message_id)) = (args.source_chain, args.message_id) else {
+        let path = args
+            .file
+            .ok_or_else(|| eyre!("Either a message or a file of messages is required"))?;
+        return batch_message_status(&rpc_client, &read_json_file_from_path(&path)?);
+    };
+
+    let lookup = MessageLookup {
+        source_chain,
+        message_id,
+        payload: args.payload,
     };
-    println!("Message status: {status}");
+    let command_id = command_id(&lookup.source_chain, &lookup.message_id);
+    let (incoming_message_pda, _) = solana_axelar_gateway::IncomingMessage::find_pda(&command_id);
+    let incoming_message = fetch_incoming_message(&rpc_client, &incoming_message_pda)?;
+
+    println!("------------------------------------------");
+    println!("Message {} from {}", lookup.message_id, lookup.source_chain);
+    println!("   Command ID: 0x{}", hex::encode(command_id));
+    println!("   Incoming message PDA: {incoming_message_pda}");
+    println!(
+        "   Status: {}",
+        incoming_message_status(incoming_message.as_ref())
+    );
+    if let Some(incoming_message) = &incoming_message {
+        println!(
+            "   Payload hash: 0x{}",
+            hex::encode(incoming_message.payload_hash)
+        );
+        if let Some(check) = payload_check(&lookup, incoming_message)? {
+            println!("   Payload: {check}");
+        }
+    }
+    if let Some(destination) = &args.destination_address {
+        let (signing_pda, bump) = signing_pda(destination, &command_id);
+        println!("   Signing PDA of {destination}: {signing_pda} (bump {bump})");
+    }
+    println!("------------------------------------------");
+
+    Ok(())
+}
+
+fn batch_message_status(rpc_client: &RpcClient, lookups: &[MessageLookup]) -> eyre::Result<()> {
+    if lookups.is_empty() {
+        eyre::bail!("No messages found in the file");
+    }
+
+    let pdas: Vec<Pubkey> = lookups
+        .iter()
+        .map(|lookup| {
+            let command_id = command_id(&lookup.source_chain, &lookup.message_id);
+            solana_axelar_gateway::IncomingMessage::find_pda(&command_id).0
+        })
+        .collect();
+
+    let mut not_approved = 0_usize;
+    let mut approved = 0_usize;
+    let mut executed = 0_usize;
+    let mut mismatches = 0_usize;
+
+    println!("------------------------------------------");
+    // getMultipleAccounts accepts at most 100 accounts per request.
+    for (lookups, pdas) in lookups.chunks(100).zip(pdas.chunks(100)) {
+        let accounts = rpc_client.get_multiple_accounts(pdas)?;
+        for (lookup, account) in lookups.iter().zip(accounts) {
+            let incoming_message = account
+                .map(|account| {
+                    use anchor_lang::AccountDeserialize;
+
+                    solana_axelar_gateway::IncomingMessage::try_deserialize(
+                        &mut account.data.get(8..).unwrap_or_default(),
+                    )
+                    .map_err(|_| eyre!("Failed to deserialize message data"))
+                })
+                .transpose()?;
+
+            match &incoming_message {
+                None => not_approved = not_approved.saturating_add(1),
+                Some(message) if message.status.is_approved() => {
+                    approved = approved.saturating_add(1);
+                }
+                Some(_) => executed = executed.saturating_add(1),
+            }
+
+            let check = match &incoming_message {
+                Some(message) => payload_check(lookup, message)?,
+                None => None,
+            };
+            if check.as_ref().is_some_and(|check| !check.matches) {
+                mismatches = mismatches.saturating_add(1);
+            }
+
+            println!(
+                "{} {}: {}{}",
+                lookup.source_chain,
+                lookup.message_id,
+                incoming_message_status(incoming_message.as_ref()),
+                check.map_or_else(String::new, |check| format!(" (payload {check})"))
+            );
+        }
+    }
+
+    println!("------------------------------------------");
+    println!(
+        "{} message(s): {not_approved} not approved, {approved} approved, {executed} executed",
+        lookups.len()
+    );
+    if mismatches > 0 {
+        println!("\u{274c} {mismatches} payload(s) do not match the approved hash");
+    }
 
     Ok(())
 }
+
+fn incoming_message_status(
+    incoming_message: Option<&solana_axelar_gateway::IncomingMessage>,
+) -> &'static str {
+    match incoming_message {
+        None => "Not approved",
+        Some(message) if message.status.is_approved() => "Approved",
+        Some(_) => "Executed",
+    }
+}
+
+/// Outcome of checking a supplied payload against the approved payload hash.
+struct PayloadCheck {
+    matches: bool,
+    payload_hash: [u8; 32],
+}
+
+impl std::fmt::Display for PayloadCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.matches {
+            write!(f, "matches the approved hash")
+        } else {
+            write!(
+                f,
+                "does NOT match the approved hash, it hashes to 0x{}",
+                hex::encode(self.payload_hash)
+            )
+        }
+    }
+}
+
+fn payload_check(
+    lookup: &MessageLookup,
+    incoming_message: &solana_axelar_gateway::IncomingMessage,
+) -> eyre::Result<Option<PayloadCheck>> {
+    let Some(payload) = &lookup.payload else {
+        return Ok(None);
+    };
+    let payload = hex::decode(payload.strip_prefix("0x").unwrap_or(payload))?;
+    let payload_hash = solana_sdk::keccak::hashv(&[&payload]).to_bytes();
+
+    Ok(Some(PayloadCheck {
+        matches: payload_hash == incoming_message.payload_hash,
+        payload_hash,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_axelar_gateway::payload::{AxelarMessagePayload, EncodingScheme};
+
+    use super::*;
+
+    /// Anchor discriminator of the executable `execute` instruction, `sha256("global:execute")`.
+    const EXECUTE_DISCRIMINATOR: [u8; 8] = [130, 221, 242, 154, 13, 193, 189, 29];
+
+    fn test_message(destination: &Pubkey, payload: &[u8]) -> Message {
+        Message {
+            cc_id: CrossChainId {
+                chain: "ethereum".to_owned(),
+                id: "0x01-1".to_owned(),
+            },
+            source_address: "0xabcd".to_owned(),
+            destination_chain: "solana".to_owned(),
+            destination_address: destination.to_string(),
+            payload_hash: solana_sdk::keccak::hashv(&[payload]).to_bytes(),
+        }
+    }
+
+    fn assert_execute_encoding(encoding_scheme: EncodingScheme) {
+        let destination = Pubkey::new_from_array([7; 32]);
+        let payload_account = AccountMeta::new(Pubkey::new_from_array([8; 32]), false);
+        let payload = AxelarMessagePayload::new(
+            b"hello",
+            std::slice::from_ref(&payload_account),
+            encoding_scheme,
+        )
+        .encode()
+        .unwrap();
+        let message = test_message(&destination, &payload);
+        let command_id = command_id(&message.cc_id.chain, &message.cc_id.id);
+        let (incoming_message_pda, _) =
+            solana_axelar_gateway::IncomingMessage::find_pda(&command_id);
+
+        let instructions = executable_instruction(
+            &destination,
+            &command_id,
+            &incoming_message_pda,
+            message.clone(),
+            payload.clone(),
+        )
+        .unwrap();
+
+        let [instruction] = instructions.as_slice() else {
+            panic!("expected a single instruction");
+        };
+        let mut expected_data = EXECUTE_DISCRIMINATOR.to_vec();
+        expected_data.extend(borsh::to_vec(&message).unwrap());
+        expected_data.extend(borsh::to_vec(&payload).unwrap());
+        assert_eq!(instruction.program_id, destination);
+        assert_eq!(instruction.data, expected_data);
+
+        let mut expected_accounts =
+            executable_accounts(&destination, &command_id, &incoming_message_pda);
+        expected_accounts.push(payload_account);
+        assert_eq!(instruction.accounts, expected_accounts);
+    }
+
+    #[test]
+    fn execute_instruction_borsh_payload() {
+        assert_execute_encoding(EncodingScheme::Borsh);
+    }
+
+    #[test]
+    fn execute_instruction_abi_payload() {
+        assert_execute_encoding(EncodingScheme::AbiEncoding);
+    }
+
+    fn test_signer_set(weights: &[u128], quorum: u128) -> SigningVerifierSet {
+        let signers = weights
+            .iter()
+            .zip(1_u8..)
+            .map(|(&weight, seed)| LocalSigner {
+                secret: k256::SecretKey::from_slice(&[seed; 32]).unwrap(),
+                weight,
+            })
+            .collect();
+        SigningVerifierSet::new_with_quorum(signers, 0, quorum)
+    }
+
+    fn selected_weights(signer_set: &SigningVerifierSet, under_sign: bool) -> Vec<u128> {
+        select_signers(signer_set, under_sign)
+            .unwrap()
+            .iter()
+            .map(|signer| signer.weight)
+            .collect()
+    }
+
+    #[test]
+    fn select_signers_reaches_quorum_with_heaviest_first() {
+        let signer_set = test_signer_set(&[1, 3, 2], 4);
+
+        assert_eq!(selected_weights(&signer_set, false), [3, 2]);
+    }
+
+    #[test]
+    fn select_signers_under_signs_below_quorum() {
+        assert_eq!(selected_weights(&test_signer_set(&[1, 3, 2], 4), true), [3]);
+        assert_eq!(
+            selected_weights(&test_signer_set(&[5, 1, 1], 4), true),
+            [1, 1]
+        );
+    }
+
+    #[test]
+    fn select_signers_cannot_under_sign_when_every_signer_reaches_quorum() {
+        let signer_set = test_signer_set(&[4, 5], 4);
+
+        assert!(select_signers(&signer_set, true).is_err());
+    }
+
+    #[test]
+    fn select_signers_insufficient_weight() {
+        let signer_set = test_signer_set(&[1, 1], 3);
+
+        assert!(select_signers(&signer_set, false).is_err());
+    }
+}
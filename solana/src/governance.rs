@@ -1,4 +1,4 @@
-use anchor_lang::InstructionData;
+use anchor_lang::{InstructionData, ToAccountMetas};
 use base64::Engine;
 use clap::{Args, Subcommand};
 use solana_sdk::instruction::AccountMeta;
@@ -7,13 +7,14 @@ use solana_sdk::pubkey::Pubkey;
 use solana_sdk::transaction::Transaction as SolanaTransaction;
 
 use crate::config::Config;
-use crate::types::{SerializableSolanaTransaction, SolanaTransactionParams};
+use crate::deploy::program_key_from_program;
+use crate::types::{Programs, SerializableSolanaTransaction, SolanaTransactionParams};
 use crate::utils::OPERATOR_KEY;
 use crate::utils::{
     ADDRESS_KEY, CHAINS_KEY, CONFIG_ACCOUNT_KEY, CONTRACTS_KEY, GOVERNANCE_ADDRESS_KEY,
     GOVERNANCE_CHAIN_KEY, GOVERNANCE_KEY, MINIMUM_PROPOSAL_ETA_DELAY_KEY, UPGRADE_AUTHORITY_KEY,
     fetch_latest_blockhash, parse_account_meta_string, read_json_file_from_path,
-    write_json_to_file_path,
+    try_infer_program_id_from_env, write_json_to_file_path,
 };
 
 #[derive(Subcommand, Debug)]
@@ -32,19 +33,19 @@ pub(crate) enum Commands {
 pub(crate) struct InitArgs {
     /// The name of the chain in charge of the governance
     #[clap(long)]
-    governance_chain: String,
+    pub(crate) governance_chain: String,
 
     /// The address of the governance contract on the governance chain
     #[clap(long)]
-    governance_address: String,
+    pub(crate) governance_address: String,
 
     /// Minimum value (in seconds) for a proposal ETA
     #[clap(long)]
-    minimum_proposal_eta_delay: u32,
+    pub(crate) minimum_proposal_eta_delay: u32,
 
     /// The account to receive the operator role on the Interchain Governance program on Solana
     #[clap(long)]
-    operator: Pubkey,
+    pub(crate) operator: Pubkey,
 }
 
 // Common arguments for proposal execution
@@ -140,6 +141,7 @@ pub(crate) fn build_transaction(
             nonce_account: None,
             nonce_authority: None,
             blockhash_for_message: blockhash.to_string(),
+            pending_update: None,
         };
 
         // Create a serializable transaction
@@ -362,3 +364,323 @@ fn execute_operator_proposal(
         data: ix_data,
     }])
 }
+
+/// Governance command carried by the GMP payload, as numbered by the governance contracts.
+#[derive(Debug, Clone, Copy)]
+enum GovernanceCommand {
+    ScheduleTimeLockProposal = 0,
+    CancelTimeLockProposal = 1,
+    ApproveOperatorProposal = 2,
+    CancelOperatorApproval = 3,
+}
+
+impl TryFrom<u64> for GovernanceCommand {
+    type Error = eyre::Error;
+
+    fn try_from(value: u64) -> eyre::Result<Self> {
+        match value {
+            0 => Ok(Self::ScheduleTimeLockProposal),
+            1 => Ok(Self::CancelTimeLockProposal),
+            2 => Ok(Self::ApproveOperatorProposal),
+            3 => Ok(Self::CancelOperatorApproval),
+            other => eyre::bail!("Unknown governance command {other}"),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct UpgradeProposalArgs {
+    /// Name of the program to upgrade
+    #[clap(long, value_enum)]
+    program: Programs,
+
+    /// Buffer holding the new program. Its authority must be the InterchainGovernance config PDA.
+    #[clap(long)]
+    buffer: Pubkey,
+
+    /// Account receiving the buffer's lamports after the upgrade
+    #[clap(long)]
+    spill: Pubkey,
+
+    /// Earliest execution time of the proposal (UNIX timestamp). The minimum proposal ETA delay
+    /// of the governance config applies on top of it.
+    #[clap(long, default_value_t = 0)]
+    eta: u64,
+
+    /// Build an operator proposal, which the operator can execute without waiting for the ETA
+    #[clap(long)]
+    operator_proposal: bool,
+}
+
+/// Build the proposal upgrading `program` from `buffer` with the InterchainGovernance config PDA
+/// as upgrade authority, and print the GMP payload to send from the governance chain.
+pub(crate) fn upgrade_proposal(args: UpgradeProposalArgs, config: &Config) -> eyre::Result<()> {
+    let env: serde_json::Value = read_json_file_from_path(&config.chains_info_file)?;
+    let program_key = program_key_from_program(&args.program);
+    let program_id = try_infer_program_id_from_env(&env, &config.chain, program_key)?;
+    let (config_pda, _) = solana_axelar_governance::GovernanceConfig::find_pda();
+
+    let execute_data = upgrade_proposal_data(&program_id, &args.buffer, &args.spill);
+    let proposal_hash = solana_axelar_governance::ExecutableProposal::hash_from_data(&execute_data);
+    let (proposal_pda, _) = solana_axelar_governance::ExecutableProposal::find_pda(&proposal_hash);
+
+    let command = if args.operator_proposal {
+        GovernanceCommand::ApproveOperatorProposal
+    } else {
+        GovernanceCommand::ScheduleTimeLockProposal
+    };
+    let gmp_payload = proposal_payload(command, &execute_data, args.eta)?;
+
+    let governance_chain = env[CHAINS_KEY][&config.chain][CONTRACTS_KEY][GOVERNANCE_KEY]
+        [GOVERNANCE_CHAIN_KEY]
+        .as_str()
+        .unwrap_or("<governance chain>");
+    let governance_address = env[CHAINS_KEY][&config.chain][CONTRACTS_KEY][GOVERNANCE_KEY]
+        [GOVERNANCE_ADDRESS_KEY]
+        .as_str()
+        .unwrap_or("<governance address>");
+
+    println!("------------------------------------------");
+    println!("Upgrade proposal for {program_key} ({program_id})");
+    println!("   Buffer: {}", args.buffer);
+    println!("   Spill: {}", args.spill);
+    println!("   Upgrade authority (governance config PDA): {config_pda}");
+    println!("   Command: {command:?}");
+    println!("   ETA: {}", args.eta);
+    println!();
+    println!("Proposal hash: 0x{}", hex::encode(proposal_hash));
+    println!("Proposal PDA: {proposal_pda}");
+    println!(
+        "Target: {}",
+        Pubkey::new_from_array(execute_data.target_address)
+    );
+    println!(
+        "Call data (base64): {}",
+        base64::engine::general_purpose::STANDARD.encode(&execute_data.call_data.call_data)
+    );
+    println!("Target accounts:");
+    for meta in &execute_data.call_data.solana_accounts {
+        println!(
+            "   --target-accounts {}:{}:{}",
+            Pubkey::new_from_array(meta.pubkey),
+            meta.is_signer,
+            meta.is_writable
+        );
+    }
+    println!();
+    println!("GMP payload to send from {governance_chain} ({governance_address}):");
+    println!("0x{}", hex::encode(gmp_payload));
+    println!("------------------------------------------");
+
+    Ok(())
+}
+
+/// Proposal invoking the loader to upgrade `program_id` from `buffer`, with the InterchainGovernance
+/// config PDA as upgrade authority.
+fn upgrade_proposal_data(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    spill: &Pubkey,
+) -> solana_axelar_governance::ExecuteProposalData {
+    let (config_pda, _) = solana_axelar_governance::GovernanceConfig::find_pda();
+    let upgrade_ix =
+        solana_loader_v3_interface::instruction::upgrade(program_id, buffer, &config_pda, spill);
+
+    // The upgrade authority stays a signer: the governance program signs for its config PDA when
+    // invoking the target.
+    let solana_accounts = upgrade_ix
+        .accounts
+        .iter()
+        .map(|meta| solana_axelar_governance::SolanaAccountMetadata {
+            pubkey: meta.pubkey.to_bytes(),
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        })
+        .collect();
+
+    solana_axelar_governance::ExecuteProposalData {
+        target_address: upgrade_ix.program_id.to_bytes(),
+        call_data: solana_axelar_governance::ExecuteProposalCallData {
+            solana_accounts,
+            solana_native_value_receiver_account: None,
+            call_data: upgrade_ix.data,
+        },
+        native_value: [0_u8; 32],
+    }
+}
+
+/// GMP payload carrying `command` for the proposal, as the governance contracts encode it:
+/// `(command, target, call data, native value, eta)`.
+fn proposal_payload(
+    command: GovernanceCommand,
+    execute_data: &solana_axelar_governance::ExecuteProposalData,
+    eta: u64,
+) -> eyre::Result<Vec<u8>> {
+    use alloy_primitives::{Bytes, U256};
+    use alloy_sol_types::SolValue;
+
+    Ok((
+        U256::from(command as u8),
+        Bytes::from(execute_data.target_address.to_vec()),
+        Bytes::from(borsh::to_vec(&execute_data.call_data)?),
+        U256::from_le_bytes(execute_data.native_value),
+        U256::from(eta),
+    )
+        .abi_encode_params())
+}
+
+/// Build the instruction processing a governance GMP message approved by the gateway, which
+/// schedules or cancels a timelock proposal, or approves or cancels an operator proposal.
+pub(crate) fn process_gmp_instructions(
+    fee_payer: &Pubkey,
+    command_id: &[u8; 32],
+    incoming_message_pda: &Pubkey,
+    message: &solana_axelar_std::message::Message,
+    payload: &[u8],
+) -> eyre::Result<Vec<Instruction>> {
+    use alloy_primitives::{Bytes, U256};
+    use alloy_sol_types::SolValue;
+
+    let (command, target, call_data, native_value, eta) =
+        <(U256, Bytes, Bytes, U256, U256)>::abi_decode_params(payload, true)
+            .map_err(|e| eyre::eyre!("Failed to decode the governance payload: {e}"))?;
+    let command = GovernanceCommand::try_from(
+        u64::try_from(command).map_err(|_| eyre::eyre!("Unknown governance command {command}"))?,
+    )?;
+
+    let target_address: [u8; 32] = target.as_ref().try_into().map_err(|_| {
+        eyre::eyre!(
+            "Invalid proposal target: expected a 32 byte Solana address, got 0x{}",
+            hex::encode(&target)
+        )
+    })?;
+    let execute_data = solana_axelar_governance::ExecuteProposalData {
+        target_address,
+        call_data: borsh::from_slice(&call_data)
+            .map_err(|e| eyre::eyre!("Failed to decode the proposal call data: {e}"))?,
+        native_value: native_value.to_le_bytes::<32>(),
+    };
+
+    let (config_pda, _) = solana_axelar_governance::GovernanceConfig::find_pda();
+    let proposal_hash = solana_axelar_governance::ExecutableProposal::hash_from_data(&execute_data);
+    let (proposal_pda, _) = solana_axelar_governance::ExecutableProposal::find_pda(&proposal_hash);
+    let (operator_proposal_pda, _) =
+        solana_axelar_governance::OperatorProposal::find_pda(&proposal_hash);
+
+    println!("------------------------------------------");
+    println!("Governance message from {}:", message.source_address);
+    println!("   Command: {command:?}");
+    println!(
+        "   Target: {}",
+        Pubkey::new_from_array(execute_data.target_address)
+    );
+    println!("   Native value: {native_value}");
+    println!("   ETA: {eta}");
+    println!("   Proposal hash: 0x{}", hex::encode(proposal_hash));
+    println!("   Proposal PDA: {proposal_pda}");
+    println!("   Operator proposal PDA: {operator_proposal_pda}");
+    println!("------------------------------------------");
+
+    let (signing_pda, _) = crate::gateway::signing_pda(&solana_axelar_governance::id(), command_id);
+    let (gateway_event_authority, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &solana_axelar_gateway::id());
+    let accounts = solana_axelar_governance::accounts::ProcessGmp {
+        incoming_message_pda: *incoming_message_pda,
+        signing_pda,
+        gateway_root_pda: solana_axelar_gateway::GatewayConfig::find_pda().0,
+        gateway_event_authority,
+        axelar_gateway_program: solana_axelar_gateway::id(),
+        payer: *fee_payer,
+        system_program: solana_sdk_ids::system_program::ID,
+        governance_config: config_pda,
+        proposal_pda,
+        operator_proposal_pda,
+    };
+    let data = solana_axelar_governance::instruction::ProcessGmp {
+        message: message.clone(),
+        payload: payload.to_vec(),
+    }
+    .data();
+
+    Ok(vec![Instruction {
+        program_id: solana_axelar_governance::id(),
+        accounts: accounts.to_account_metas(None),
+        data,
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Bytes, U256};
+    use alloy_sol_types::SolValue;
+
+    use super::*;
+
+    #[test]
+    fn test_upgrade_proposal_keeps_authority_signer() {
+        let (config_pda, _) = solana_axelar_governance::GovernanceConfig::find_pda();
+        let execute_data = upgrade_proposal_data(
+            &Pubkey::new_from_array([1; 32]),
+            &Pubkey::new_from_array([2; 32]),
+            &Pubkey::new_from_array([3; 32]),
+        );
+
+        assert_eq!(
+            execute_data.target_address,
+            solana_sdk_ids::bpf_loader_upgradeable::ID.to_bytes()
+        );
+        let authority = execute_data
+            .call_data
+            .solana_accounts
+            .iter()
+            .find(|meta| meta.pubkey == config_pda.to_bytes())
+            .unwrap();
+        assert!(authority.is_signer);
+        assert!(!authority.is_writable);
+    }
+
+    #[test]
+    fn test_proposal_payload_encoding() {
+        let execute_data = upgrade_proposal_data(
+            &Pubkey::new_from_array([1; 32]),
+            &Pubkey::new_from_array([2; 32]),
+            &Pubkey::new_from_array([3; 32]),
+        );
+        let payload = proposal_payload(
+            GovernanceCommand::ApproveOperatorProposal,
+            &execute_data,
+            1_700_000_000,
+        )
+        .unwrap();
+
+        // Static head: command, offsets of the target and of the call data, native value, eta.
+        let word = |i: usize| &payload[i * 32..(i + 1) * 32];
+        assert_eq!(word(0), U256::from(2_u8).to_be_bytes::<32>());
+        assert_eq!(word(1), U256::from(0xa0_u32).to_be_bytes::<32>());
+        assert_eq!(word(2), U256::from(0xe0_u32).to_be_bytes::<32>());
+        assert_eq!(word(3), [0_u8; 32]);
+        assert_eq!(word(4), U256::from(1_700_000_000_u64).to_be_bytes::<32>());
+        assert_eq!(word(5), U256::from(32_u8).to_be_bytes::<32>());
+        assert_eq!(word(6), execute_data.target_address);
+
+        // The governance program hashes the proposal it decodes from the payload: it must be the
+        // proposal whose hash is printed.
+        let (command, target, call_data, native_value, eta) =
+            <(U256, Bytes, Bytes, U256, U256)>::abi_decode_params(&payload, true).unwrap();
+        assert_eq!(command, U256::from(2_u8));
+        assert_eq!(eta, U256::from(1_700_000_000_u64));
+        assert_eq!(
+            call_data.as_ref(),
+            borsh::to_vec(&execute_data.call_data).unwrap()
+        );
+        let decoded = solana_axelar_governance::ExecuteProposalData {
+            target_address: target.as_ref().try_into().unwrap(),
+            call_data: borsh::from_slice(&call_data).unwrap(),
+            native_value: native_value.to_le_bytes::<32>(),
+        };
+        assert_eq!(
+            solana_axelar_governance::ExecutableProposal::hash_from_data(&decoded),
+            solana_axelar_governance::ExecutableProposal::hash_from_data(&execute_data)
+        );
+    }
+}
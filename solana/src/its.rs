@@ -51,10 +51,27 @@ struct FlowSlot {
     _epoch: u64,
 }
 
-fn find_its_root_pda() -> (Pubkey, u8) {
+pub(crate) fn find_its_root_pda() -> (Pubkey, u8) {
     Pubkey::find_program_address(&[ITS_SEED], &solana_axelar_its::id())
 }
 
+/// Fetch the chains currently trusted by the Interchain Token Service.
+pub(crate) fn fetch_trusted_chains(rpc_client: &RpcClient) -> eyre::Result<Vec<String>> {
+    use borsh::BorshDeserialize;
+
+    let (its_root_pda, _) = find_its_root_pda();
+    let its_account = rpc_client.get_account(&its_root_pda)?;
+
+    let mut data = &*its_account.data;
+    let _discriminator = <[u8; 8]>::deserialize(&mut data)?;
+    let _its_hub_address = String::deserialize(&mut data)?;
+    let _chain_name = String::deserialize(&mut data)?;
+    let _paused = bool::deserialize(&mut data)?;
+    let trusted_chains = Vec::<String>::deserialize(&mut data)?;
+
+    Ok(trusted_chains)
+}
+
 fn find_token_manager_pda(its_root_pda: &Pubkey, token_id: &[u8; 32]) -> (Pubkey, u8) {
     Pubkey::find_program_address(
         &[TOKEN_MANAGER_SEED, its_root_pda.as_ref(), token_id],
@@ -322,15 +339,15 @@ pub(crate) struct InterchainTokenMintArgs {
 pub(crate) struct InitArgs {
     /// The operator account for the Interchain Token Service
     #[clap(short, long)]
-    operator: Pubkey,
+    pub(crate) operator: Pubkey,
 
     /// The chain name for the Interchain Token Service
     #[clap(long)]
-    chain_name: String,
+    pub(crate) chain_name: String,
 
     /// The ITS hub address on the Axelar network
     #[clap(long)]
-    its_hub_address: String,
+    pub(crate) its_hub_address: String,
 }
 
 #[derive(Parser, Debug)]
@@ -343,11 +360,11 @@ pub(crate) struct SetPauseStatusArgs {
 #[derive(Parser, Debug)]
 pub(crate) struct TrustedChainArgs {
     /// The name of the chain to set as trusted
-    chain_name: String,
+    pub(crate) chain_name: String,
 
     /// The authority account (ITS operator or upgrade authority)
     #[clap(long)]
-    authority: Option<Pubkey>,
+    pub(crate) authority: Option<Pubkey>,
 }
 
 #[derive(Parser, Debug)]
@@ -738,6 +755,7 @@ pub(crate) fn build_transaction(
             nonce_account: None,
             nonce_authority: None,
             blockhash_for_message: blockhash.to_string(),
+            pending_update: None,
         };
 
         // Create a serializable transaction
@@ -866,16 +884,7 @@ fn set_trusted_chain(
     }
 
     if set_trusted_chain_args.chain_name == "all" {
-        use borsh::BorshDeserialize;
-
-        let its_account = rpc_client.get_account(&its_root_pda)?;
-
-        let mut data = &*its_account.data;
-        let _discriminator = <[u8; 8]>::deserialize(&mut data)?;
-        let _its_hub_address = String::deserialize(&mut data)?;
-        let _chain_name = String::deserialize(&mut data)?;
-        let _paused = bool::deserialize(&mut data)?;
-        let trusted_chains = Vec::<String>::deserialize(&mut data)?;
+        let trusted_chains = fetch_trusted_chains(&rpc_client)?;
 
         let chains_info: serde_json::Value = read_json_file_from_path(&config.chains_info_file)?;
 
@@ -2004,6 +2013,279 @@ fn interchain_token_mint(
     }])
 }
 
+/// ITS message types, as encoded in the first word of an ITS payload.
+const MESSAGE_TYPE_INTERCHAIN_TRANSFER: u64 = 0;
+const MESSAGE_TYPE_DEPLOY_INTERCHAIN_TOKEN: u64 = 1;
+const MESSAGE_TYPE_RECEIVE_FROM_HUB: u64 = 4;
+const MESSAGE_TYPE_LINK_TOKEN: u64 = 5;
+
+/// Inbound ITS message, decoded from the payload wrapped by the ITS hub.
+#[derive(Debug)]
+enum InboundItsMessage {
+    InterchainTransfer {
+        token_id: [u8; 32],
+        destination_address: Vec<u8>,
+        amount: alloy_primitives::U256,
+        data: Vec<u8>,
+    },
+    DeployInterchainToken {
+        token_id: [u8; 32],
+        name: String,
+        symbol: String,
+        decimals: u8,
+        minter: Vec<u8>,
+    },
+    LinkToken {
+        token_id: [u8; 32],
+        destination_token_address: Vec<u8>,
+        params: Vec<u8>,
+    },
+}
+
+fn abi_message_type(payload: &[u8]) -> eyre::Result<u64> {
+    use alloy_sol_types::SolValue;
+
+    let word = payload
+        .get(..32)
+        .ok_or_else(|| eyre!("ITS payload is too short"))?;
+    let message_type = alloy_primitives::U256::abi_decode(word, true)
+        .map_err(|e| eyre!("Failed to decode the ITS message type: {e}"))?;
+    u64::try_from(message_type).map_err(|_| eyre!("Unknown ITS message type {message_type}"))
+}
+
+/// Decode an ITS hub `ReceiveFromHub` payload into the source chain and the inner ITS message.
+fn decode_hub_payload(payload: &[u8]) -> eyre::Result<(String, InboundItsMessage)> {
+    use alloy_primitives::{B256, Bytes, U256};
+    use alloy_sol_types::SolValue;
+
+    if abi_message_type(payload)? != MESSAGE_TYPE_RECEIVE_FROM_HUB {
+        eyre::bail!("ITS messages must be routed through the ITS hub (ReceiveFromHub)");
+    }
+    let (_, source_chain, inner) = <(U256, String, Bytes)>::abi_decode_params(payload, true)
+        .map_err(|e| eyre!("Failed to decode the ITS hub payload: {e}"))?;
+
+    let message = match abi_message_type(&inner)? {
+        MESSAGE_TYPE_INTERCHAIN_TRANSFER => {
+            let (_, token_id, _source_address, destination_address, amount, data) =
+                <(U256, B256, Bytes, Bytes, U256, Bytes)>::abi_decode_params(&inner, true)
+                    .map_err(|e| eyre!("Failed to decode InterchainTransfer: {e}"))?;
+            InboundItsMessage::InterchainTransfer {
+                token_id: token_id.0,
+                destination_address: destination_address.to_vec(),
+                amount,
+                data: data.to_vec(),
+            }
+        }
+        MESSAGE_TYPE_DEPLOY_INTERCHAIN_TOKEN => {
+            let (_, token_id, name, symbol, decimals, minter) =
+                <(U256, B256, String, String, u8, Bytes)>::abi_decode_params(&inner, true)
+                    .map_err(|e| eyre!("Failed to decode DeployInterchainToken: {e}"))?;
+            InboundItsMessage::DeployInterchainToken {
+                token_id: token_id.0,
+                name,
+                symbol,
+                decimals,
+                minter: minter.to_vec(),
+            }
+        }
+        MESSAGE_TYPE_LINK_TOKEN => {
+            let (_, token_id, _token_manager_type, _source_token, destination_token, params) =
+                <(U256, B256, U256, Bytes, Bytes, Bytes)>::abi_decode_params(&inner, true)
+                    .map_err(|e| eyre!("Failed to decode LinkToken: {e}"))?;
+            InboundItsMessage::LinkToken {
+                token_id: token_id.0,
+                destination_token_address: destination_token.to_vec(),
+                params: params.to_vec(),
+            }
+        }
+        other => eyre::bail!("ITS message type {other} can't be executed on Solana"),
+    };
+
+    Ok((source_chain, message))
+}
+
+fn pubkey_from_bytes(bytes: &[u8], what: &str) -> eyre::Result<Pubkey> {
+    Pubkey::try_from(bytes).map_err(|_| {
+        eyre!(
+            "Invalid {what}: expected a 32 byte Solana address, got 0x{}",
+            hex::encode(bytes)
+        )
+    })
+}
+
+/// Build the ITS `execute` instruction for an approved ITS hub message, deriving the accounts
+/// required by the message type.
+#[allow(clippy::too_many_lines)]
+pub(crate) fn execute_instructions(
+    fee_payer: &Pubkey,
+    command_id: &[u8; 32],
+    incoming_message_pda: &Pubkey,
+    message: &solana_axelar_std::message::Message,
+    payload: &[u8],
+    config: &Config,
+) -> eyre::Result<Vec<Instruction>> {
+    let (source_chain, its_message) = decode_hub_payload(payload)?;
+
+    let (its_root_pda, _) = find_its_root_pda();
+    let (event_authority, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &solana_axelar_its::id());
+    let associated_token_program = spl_associated_token_account_program_id();
+
+    println!("------------------------------------------");
+    println!("\u{1FA99} ITS message from {source_chain}:");
+    println!();
+
+    let token_id = match &its_message {
+        InboundItsMessage::InterchainTransfer { token_id, .. }
+        | InboundItsMessage::DeployInterchainToken { token_id, .. }
+        | InboundItsMessage::LinkToken { token_id, .. } => *token_id,
+    };
+    let (token_manager_pda, _) = find_token_manager_pda(&its_root_pda, &token_id);
+    println!("- Interchain Token ID: {}", hex::encode(token_id));
+    println!("- Token Manager: {token_manager_pda}");
+
+    let (mint, token_program) = match &its_message {
+        InboundItsMessage::InterchainTransfer { .. } => {
+            let mint = get_mint_from_token_manager(&token_id, config)?;
+            (mint, get_token_program_from_mint(&mint, config)?)
+        }
+        InboundItsMessage::DeployInterchainToken { .. } => (
+            find_interchain_token_pda(&its_root_pda, &token_id).0,
+            anchor_spl::token_2022::ID,
+        ),
+        InboundItsMessage::LinkToken {
+            destination_token_address,
+            ..
+        } => {
+            let mint = pubkey_from_bytes(destination_token_address, "destination token address")?;
+            (mint, get_token_program_from_mint(&mint, config)?)
+        }
+    };
+    let token_manager_ata = get_associated_token_address(&token_manager_pda, &mint, &token_program);
+
+    let (signing_pda, _) = crate::gateway::signing_pda(&solana_axelar_its::id(), command_id);
+    let (gateway_event_authority, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &solana_axelar_gateway::id());
+
+    let mut accounts = solana_axelar_its::accounts::Execute {
+        incoming_message_pda: *incoming_message_pda,
+        signing_pda,
+        gateway_root_pda: solana_axelar_gateway::GatewayConfig::find_pda().0,
+        gateway_event_authority,
+        axelar_gateway_program: solana_axelar_gateway::id(),
+        payer: *fee_payer,
+        system_program: solana_sdk_ids::system_program::ID,
+        its_root_pda,
+        token_manager_pda,
+        token_mint: mint,
+        token_manager_ata,
+        token_program,
+        associated_token_program,
+        event_authority,
+        program: solana_axelar_its::id(),
+    }
+    .to_account_metas(None);
+
+    match its_message {
+        InboundItsMessage::InterchainTransfer {
+            destination_address,
+            amount,
+            data,
+            ..
+        } => {
+            let destination = pubkey_from_bytes(&destination_address, "destination address")?;
+            let destination_ata = get_associated_token_address(&destination, &mint, &token_program);
+            println!("- Type: InterchainTransfer");
+            println!("- Mint: {mint}");
+            println!("- Amount: {amount}");
+            println!("- Destination: {destination}");
+            println!("- Destination ATA: {destination_ata}");
+
+            accounts.push(AccountMeta::new(destination, false));
+            accounts.push(AccountMeta::new(destination_ata, false));
+
+            // Transfers with data call the destination program, with the accounts encoded in
+            // the data.
+            if !data.is_empty() {
+                let decoded = solana_axelar_gateway::payload::AxelarMessagePayload::decode(&data)
+                    .map_err(|e| {
+                    eyre!("Failed to decode the transfer data as an AxelarMessagePayload: {e:?}")
+                })?;
+                let data_accounts = decoded.account_meta();
+                println!("- Executable accounts: {}", data_accounts.len());
+                accounts.extend(data_accounts);
+            }
+        }
+        InboundItsMessage::DeployInterchainToken {
+            name,
+            symbol,
+            decimals,
+            minter,
+            ..
+        } => {
+            let mpl_token_metadata_program = mpl_token_metadata_program_id();
+            let (metadata_account, _) = Pubkey::find_program_address(
+                &[
+                    b"metadata",
+                    mpl_token_metadata_program.as_ref(),
+                    mint.as_ref(),
+                ],
+                &mpl_token_metadata_program,
+            );
+            println!("- Type: DeployInterchainToken");
+            println!("- Mint: {mint}");
+            println!("- Name: {name}");
+            println!("- Symbol: {symbol}");
+            println!("- Decimals: {decimals}");
+
+            accounts.push(AccountMeta::new_readonly(
+                solana_sdk::sysvar::instructions::id(),
+                false,
+            ));
+            accounts.push(AccountMeta::new_readonly(mpl_token_metadata_program, false));
+            accounts.push(AccountMeta::new(metadata_account, false));
+
+            if !minter.is_empty() {
+                let minter = pubkey_from_bytes(&minter, "minter")?;
+                let (minter_roles, _) = Pubkey::find_program_address(
+                    &[b"user-roles", token_manager_pda.as_ref(), minter.as_ref()],
+                    &solana_axelar_its::id(),
+                );
+                println!("- Minter: {minter}");
+                accounts.push(AccountMeta::new_readonly(minter, false));
+                accounts.push(AccountMeta::new(minter_roles, false));
+            }
+        }
+        InboundItsMessage::LinkToken { params, .. } => {
+            println!("- Type: LinkToken");
+            println!("- Mint: {mint}");
+
+            // The link params optionally carry the operator of the token manager.
+            if !params.is_empty() {
+                let operator = pubkey_from_bytes(&params, "token manager operator")?;
+                let (operator_roles, _) = Pubkey::find_program_address(
+                    &[b"user-roles", token_manager_pda.as_ref(), operator.as_ref()],
+                    &solana_axelar_its::id(),
+                );
+                println!("- Operator: {operator}");
+                accounts.push(AccountMeta::new_readonly(operator, false));
+                accounts.push(AccountMeta::new(operator_roles, false));
+            }
+        }
+    }
+    println!("------------------------------------------");
+
+    Ok(vec![Instruction {
+        program_id: solana_axelar_its::id(),
+        accounts,
+        data: solana_axelar_its::instruction::Execute {
+            message: message.clone(),
+            payload: payload.to_vec(),
+        }
+        .data(),
+    }])
+}
+
 pub(crate) fn query(command: QueryCommands, config: &Config) -> eyre::Result<()> {
     match command {
         QueryCommands::TokenManager(mint_args) => get_token_manager(mint_args, config),
@@ -2034,3 +2316,136 @@ fn get_token_manager(args: TokenManagerArgs, config: &Config) -> eyre::Result<()
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{B256, Bytes, U256};
+    use alloy_sol_types::SolValue;
+
+    use super::*;
+
+    const TOKEN_ID: [u8; 32] = [7; 32];
+
+    fn hub_payload(source_chain: &str, inner: Vec<u8>) -> Vec<u8> {
+        (
+            U256::from(MESSAGE_TYPE_RECEIVE_FROM_HUB),
+            source_chain.to_owned(),
+            Bytes::from(inner),
+        )
+            .abi_encode_params()
+    }
+
+    #[test]
+    fn decode_hub_payload_interchain_transfer() {
+        let destination = Pubkey::new_unique();
+        let inner = (
+            U256::from(MESSAGE_TYPE_INTERCHAIN_TRANSFER),
+            B256::from(TOKEN_ID),
+            Bytes::from(vec![0xab; 20]),
+            Bytes::from(destination.to_bytes().to_vec()),
+            U256::from(1_000_u64),
+            Bytes::from(vec![1, 2, 3]),
+        )
+            .abi_encode_params();
+
+        let (source_chain, message) = decode_hub_payload(&hub_payload("ethereum", inner)).unwrap();
+
+        assert_eq!(source_chain, "ethereum");
+        let InboundItsMessage::InterchainTransfer {
+            token_id,
+            destination_address,
+            amount,
+            data,
+        } = message
+        else {
+            panic!("expected an InterchainTransfer, got {message:?}");
+        };
+        assert_eq!(token_id, TOKEN_ID);
+        assert_eq!(destination_address, destination.to_bytes());
+        assert_eq!(amount, U256::from(1_000_u64));
+        assert_eq!(data, [1, 2, 3]);
+    }
+
+    #[test]
+    fn decode_hub_payload_deploy_interchain_token() {
+        let inner = (
+            U256::from(MESSAGE_TYPE_DEPLOY_INTERCHAIN_TOKEN),
+            B256::from(TOKEN_ID),
+            "Token".to_owned(),
+            "TKN".to_owned(),
+            9_u8,
+            Bytes::new(),
+        )
+            .abi_encode_params();
+
+        let (_, message) = decode_hub_payload(&hub_payload("ethereum", inner)).unwrap();
+
+        let InboundItsMessage::DeployInterchainToken {
+            token_id,
+            name,
+            symbol,
+            decimals,
+            minter,
+        } = message
+        else {
+            panic!("expected a DeployInterchainToken, got {message:?}");
+        };
+        assert_eq!(token_id, TOKEN_ID);
+        assert_eq!(name, "Token");
+        assert_eq!(symbol, "TKN");
+        assert_eq!(decimals, 9);
+        assert!(minter.is_empty());
+    }
+
+    #[test]
+    fn decode_hub_payload_link_token() {
+        let mint = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+        let inner = (
+            U256::from(MESSAGE_TYPE_LINK_TOKEN),
+            B256::from(TOKEN_ID),
+            U256::from(2_u64),
+            Bytes::from(vec![0xcd; 20]),
+            Bytes::from(mint.to_bytes().to_vec()),
+            Bytes::from(operator.to_bytes().to_vec()),
+        )
+            .abi_encode_params();
+
+        let (_, message) = decode_hub_payload(&hub_payload("ethereum", inner)).unwrap();
+
+        let InboundItsMessage::LinkToken {
+            token_id,
+            destination_token_address,
+            params,
+        } = message
+        else {
+            panic!("expected a LinkToken, got {message:?}");
+        };
+        assert_eq!(token_id, TOKEN_ID);
+        assert_eq!(destination_token_address, mint.to_bytes());
+        assert_eq!(params, operator.to_bytes());
+    }
+
+    #[test]
+    fn decode_hub_payload_requires_the_hub_wrapper() {
+        let inner = (
+            U256::from(MESSAGE_TYPE_INTERCHAIN_TRANSFER),
+            B256::from(TOKEN_ID),
+            Bytes::new(),
+            Bytes::new(),
+            U256::ZERO,
+            Bytes::new(),
+        )
+            .abi_encode_params();
+
+        assert!(decode_hub_payload(&inner).is_err());
+    }
+
+    #[test]
+    fn decode_hub_payload_rejects_unsupported_message_types() {
+        let inner = (U256::from(3_u64), B256::from(TOKEN_ID)).abi_encode_params();
+
+        assert!(decode_hub_payload(&hub_payload("ethereum", inner)).is_err());
+        assert!(decode_hub_payload(&[0; 16]).is_err());
+    }
+}
@@ -0,0 +1,309 @@
+//! Simulation of Axelar traffic on a local validator: batches of messages described in a JSON
+//! file are signed by a local SignerSet into `execute_data`, approved on the gateway and
+//! optionally executed on their destination programs, without an amplifier deployment.
+
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use eyre::eyre;
+use serde::Deserialize;
+use solana_axelar_std::execute_data::{ExecuteData, Payload};
+use solana_axelar_std::message::{CrossChainId, Message, Messages};
+use solana_sdk::signer::Signer;
+
+use crate::config::Config;
+use crate::deploy::load_fee_payer;
+use crate::gateway;
+use crate::send::{SendArgs, sign_and_send_transactions};
+use crate::types::NetworkType;
+use crate::utils::{self, domain_separator, read_json_file_from_path};
+use crate::verification::DEFAULT_VERIFICATION_PARALLELISM;
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Commands {
+    /// Sign a batch of messages with a local SignerSet and save the resulting `execute_data`, to
+    /// be submitted with `send gateway submit-proof --execute-data`
+    ForgeProof(ForgeProofArgs),
+
+    /// Sign a batch of messages with a local SignerSet, approve them on the gateway and
+    /// optionally execute them
+    Deliver(DeliverArgs),
+}
+
+/// The messages and the SignerSet signing them.
+#[derive(Args, Debug)]
+#[clap(
+    group(
+        ArgGroup::new("signing_set")
+        .args(&["signer", "signers-file"])
+        .multiple(false)
+        .required(true))
+    )
+]
+pub(crate) struct BatchArgs {
+    /// JSON file with the messages of the batch:
+    /// `[{"source_chain": "...", "message_id": "<optional>", "source_address": "...",
+    /// "destination_address": "<program id>", "payload": "<hex>"}]`
+    #[clap(long)]
+    messages: PathBuf,
+
+    /// Hex string with secp256k1 private key of the signer, the only member of the SignerSet
+    #[clap(long, value_parser=utils::parse_secret_key, value_hint=clap::ValueHint::AnyPath)]
+    signer: Option<k256::SecretKey>,
+
+    /// JSON or TOML file with the weighted signers of the SignerSet and its quorum, as accepted
+    /// by `gateway approve`
+    #[clap(long)]
+    signers_file: Option<PathBuf>,
+
+    /// Nonce associated with the SignerSet, which has to be the one registered with the gateway
+    #[clap(long)]
+    nonce: u64,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct ForgeProofArgs {
+    #[clap(flatten)]
+    batch: BatchArgs,
+
+    /// File the hex encoded `execute_data` is written to. Defaults to
+    /// `localnet-execute-data.hex` in the output directory.
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct DeliverArgs {
+    #[clap(flatten)]
+    batch: BatchArgs,
+
+    /// Execute each message on its destination program once the batch is approved
+    #[clap(long)]
+    execute: bool,
+
+    /// Fee payer signer: keypair path or usb://ledger. Defaults to the Solana CLI keypair.
+    #[clap(long)]
+    fee_payer: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct MessageEntry {
+    source_chain: String,
+    #[serde(default)]
+    message_id: Option<String>,
+    source_address: String,
+    destination_address: String,
+    payload: String,
+}
+
+/// A message of the batch along with its payload, needed to execute it.
+struct BatchMessage {
+    message: Message,
+    payload: Vec<u8>,
+}
+
+pub(crate) fn handle_command(command: Commands, config: &Config) -> eyre::Result<()> {
+    if config.network_type != NetworkType::Local {
+        eyre::bail!("localnet commands only run against a local validator");
+    }
+
+    match command {
+        Commands::ForgeProof(args) => forge_proof(args, config),
+        Commands::Deliver(args) => deliver(args, config),
+    }
+}
+
+/// Read the messages file. Messages without an id get a unique one in the
+/// `0x<hash>-<index>` format of EVM message ids.
+fn load_messages(args: &BatchArgs, config: &Config) -> eyre::Result<Vec<BatchMessage>> {
+    let entries: Vec<MessageEntry> = read_json_file_from_path(&args.messages)?;
+    if entries.is_empty() {
+        eyre::bail!("No messages in {}", args.messages.display());
+    }
+    let nonce = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_nanos()
+        .to_le_bytes();
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let payload = hex::decode(entry.payload.strip_prefix("0x").unwrap_or(&entry.payload))
+                .map_err(|e| eyre!("Invalid payload of message {index}: {e}"))?;
+            let message_id = entry.message_id.unwrap_or_else(|| {
+                let hash = solana_sdk::keccak::hashv(&[
+                    entry.source_chain.as_bytes(),
+                    &nonce,
+                    &index.to_le_bytes(),
+                ]);
+                format!("0x{}-{index}", hex::encode(hash.to_bytes()))
+            });
+
+            Ok(BatchMessage {
+                message: Message {
+                    cc_id: CrossChainId {
+                        chain: entry.source_chain,
+                        id: message_id,
+                    },
+                    source_address: entry.source_address,
+                    destination_chain: config.chain.clone(),
+                    destination_address: entry.destination_address,
+                    payload_hash: solana_sdk::keccak::hashv(&[&payload]).to_bytes(),
+                },
+                payload,
+            })
+        })
+        .collect()
+}
+
+fn forge_execute_data(
+    args: &BatchArgs,
+    messages: &[BatchMessage],
+    config: &Config,
+) -> eyre::Result<ExecuteData> {
+    let chains_info: serde_json::Value = read_json_file_from_path(&config.chains_info_file)?;
+    let signer_set = gateway::signing_verifier_set(
+        args.signer.clone(),
+        args.signers_file.as_deref(),
+        args.nonce,
+    )?;
+    let payload = Payload::Messages(Messages(
+        messages.iter().map(|batch| batch.message.clone()).collect(),
+    ));
+
+    gateway::construct_execute_data(
+        &signer_set,
+        payload,
+        domain_separator(&chains_info, config.network_type, &config.chain)?,
+        false,
+    )
+}
+
+fn print_batch(messages: &[BatchMessage]) {
+    println!("------------------------------------------");
+    println!("Batch of {} message(s):", messages.len());
+    for batch in messages {
+        println!(
+            "   {} from {} ({}) to {}",
+            batch.message.cc_id.id,
+            batch.message.cc_id.chain,
+            batch.message.source_address,
+            batch.message.destination_address
+        );
+    }
+    println!("------------------------------------------");
+}
+
+fn forge_proof(args: ForgeProofArgs, config: &Config) -> eyre::Result<()> {
+    let messages = load_messages(&args.batch, config)?;
+    let execute_data = forge_execute_data(&args.batch, &messages, config)?;
+    let output = args
+        .output
+        .unwrap_or_else(|| config.output_dir.join("localnet-execute-data.hex"));
+
+    std::fs::write(&output, hex::encode(borsh::to_vec(&execute_data)?))?;
+
+    print_batch(&messages);
+    println!(
+        "Payload merkle root: 0x{}",
+        hex::encode(execute_data.payload_merkle_root)
+    );
+    println!("Saved execute_data to {}", output.display());
+
+    Ok(())
+}
+
+fn deliver(args: DeliverArgs, config: &Config) -> eyre::Result<()> {
+    // Loaded once and shared by every send, so that a Ledger is only prompted once.
+    let fee_payer: Rc<dyn Signer> = Rc::from(load_fee_payer(args.fee_payer.as_deref())?);
+    let fee_payer_pubkey = fee_payer.pubkey();
+    let messages = load_messages(&args.batch, config)?;
+    let execute_data = forge_execute_data(&args.batch, &messages, config)?;
+    print_batch(&messages);
+
+    let gateway_config_pda = solana_axelar_gateway::GatewayConfig::find_pda().0;
+    let mut instructions = Vec::new();
+    let verification_session_pda = gateway::append_verification_flow_instructions(
+        &fee_payer_pubkey,
+        &mut instructions,
+        &execute_data,
+        &gateway_config_pda,
+    )?;
+    // Forged batches only carry messages, there is no rotation to pass the operator to.
+    gateway::append_payload_instructions(
+        &fee_payer_pubkey,
+        None,
+        &mut instructions,
+        &execute_data,
+        &verification_session_pda,
+        &gateway_config_pda,
+    );
+
+    let transactions = gateway::serializable_transactions(
+        &fee_payer_pubkey,
+        instructions
+            .into_iter()
+            .map(|instruction| vec![instruction])
+            .collect(),
+        config,
+    )?;
+    sign_and_send_transactions(
+        SendArgs {
+            fee_payer: Box::new(Rc::clone(&fee_payer)),
+            signers: Vec::new(),
+            verification_parallelism: DEFAULT_VERIFICATION_PARALLELISM,
+        },
+        config,
+        transactions,
+    )?;
+    println!("\u{2705} Approved {} message(s)", messages.len());
+
+    if !args.execute {
+        return Ok(());
+    }
+
+    let mut failed = Vec::new();
+    for batch in messages {
+        let message_id = batch.message.cc_id.id.clone();
+        let result =
+            gateway::execute_message(&fee_payer_pubkey, batch.message, batch.payload, config)
+                .and_then(|instructions| {
+                    gateway::serializable_transactions(
+                        &fee_payer_pubkey,
+                        instructions
+                            .into_iter()
+                            .map(|instruction| vec![instruction])
+                            .collect(),
+                        config,
+                    )
+                })
+                .and_then(|transactions| {
+                    sign_and_send_transactions(
+                        SendArgs {
+                            fee_payer: Box::new(Rc::clone(&fee_payer)),
+                            signers: Vec::new(),
+                            verification_parallelism: DEFAULT_VERIFICATION_PARALLELISM,
+                        },
+                        config,
+                        transactions,
+                    )
+                });
+        if let Err(err) = result {
+            eprintln!("\u{274c} Failed to execute message {message_id}: {err}");
+            failed.push(message_id);
+        }
+    }
+
+    if !failed.is_empty() {
+        eyre::bail!(
+            "{} message(s) failed to execute: {}",
+            failed.len(),
+            failed.join(", ")
+        );
+    }
+    println!("\u{2705} Executed all messages");
+
+    Ok(())
+}
@@ -3,20 +3,30 @@ mod broadcast;
 mod combine;
 mod config;
 mod deploy;
+mod drift;
+mod events;
 mod gas_service;
 mod gateway;
 mod generate;
 mod governance;
 mod its;
 mod load_test;
+mod localnet;
 mod memo;
 mod misc;
+mod multicall;
 mod multisig_prover_types;
 mod operators;
+mod program;
+mod program_loader;
+mod reclaim;
+mod relay;
 mod send;
 mod sign;
+mod stack;
 mod types;
 mod utils;
+mod verification;
 
 use std::path::PathBuf;
 use std::process::exit;
@@ -37,9 +47,11 @@ use types::{AxelarNetwork, SerializableSolanaTransaction};
 use crate::broadcast::broadcast_solana_transaction;
 use crate::combine::combine_solana_signatures;
 use crate::config::Config;
-use crate::deploy::{DeployArgs, UpgradeArgs};
+use crate::deploy::{DeployArgs, UpgradeArgs, WriteBufferArgs};
+use crate::drift::DriftArgs;
 use crate::generate::generate_from_transactions;
 use crate::misc::do_misc;
+use crate::program::VerifyProgramArgs;
 use crate::sign::sign_solana_transaction;
 
 /// A CLI tool to generate, sign (offline/Ledger), combine, and broadcast Solana transactions
@@ -82,6 +94,16 @@ enum Command {
     /// Upgrade an existing Solana program (looks up program ID from env JSON)
     Upgrade(UpgradeArgs),
 
+    /// Write a program to a buffer and hand the buffer over to the upgrade authority, for
+    /// upgrades signed offline with `generate program upgrade`
+    WriteBuffer(WriteBufferArgs),
+
+    /// Check that a deployed program matches a release artifact or a local build
+    VerifyProgram(VerifyProgramArgs),
+
+    /// Compare the Solana contracts in the chains info file with their on-chain state
+    Drift(DriftArgs),
+
     /// Build and send a transaction to the Solana network.
     Send(SendCommandArgs),
 
@@ -104,9 +126,30 @@ enum Command {
     /// Query data from Solana.
     Query(QueryCommandArgs),
 
+    /// Deploy and initialize the full Axelar stack from a declarative plan.
+    #[clap(subcommand)]
+    Stack(stack::Commands),
+
+    /// Manage the local cache of downloaded program artifacts.
+    #[clap(subcommand)]
+    Artifacts(artifact::Commands),
+
+    /// Long-running services operating the AxelarGateway program.
+    #[clap(subcommand)]
+    Gateway(gateway::ServiceCommands),
+
+    /// Find and close accounts holding rent that is no longer needed.
+    #[clap(subcommand)]
+    Reclaim(reclaim::Commands),
+
     /// Load testing tools for ITS operations.
     #[clap(subcommand)]
     LoadTest(load_test::Commands),
+
+    /// Simulate Axelar traffic on a local validator by signing message batches with a local
+    /// SignerSet.
+    #[clap(subcommand)]
+    Localnet(localnet::Commands),
 }
 
 #[derive(Parser, Debug)]
@@ -120,6 +163,10 @@ struct SendCommandArgs {
     #[clap(long, short)]
     signer_keys: Vec<String>,
 
+    /// Number of gateway signature verification transactions sent at once
+    #[clap(long, default_value_t = verification::DEFAULT_VERIFICATION_PARALLELISM)]
+    verification_parallelism: usize,
+
     #[clap(subcommand)]
     instruction: InstructionSubcommand,
 }
@@ -171,6 +218,14 @@ enum InstructionSubcommand {
     /// Commands to interface with the AxelarMemo program on Solana
     #[clap(subcommand)]
     Memo(memo::Commands),
+
+    /// Commands to interface with the Multicall program on Solana
+    #[clap(subcommand)]
+    Multicall(multicall::Commands),
+
+    /// Commands to interface with the upgradeable loader for Axelar programs
+    #[clap(subcommand)]
+    Program(program::Commands),
 }
 
 #[derive(Parser, Debug)]
@@ -229,6 +284,18 @@ enum QueryInstructionSubcommand {
     // Commands to query data from InterchainTokenService program on Solana
     #[clap(subcommand)]
     Its(its::QueryCommands),
+
+    /// Commands to query data from the Multicall program on Solana
+    #[clap(subcommand)]
+    Multicall(multicall::QueryCommands),
+
+    /// Commands to scan the transaction history of Axelar programs for events
+    #[clap(subcommand)]
+    Events(events::QueryCommands),
+
+    /// Commands to query the upgradeable loader state of Axelar programs
+    #[clap(subcommand)]
+    Program(program::QueryCommands),
 }
 
 #[tokio::main]
@@ -283,6 +350,15 @@ async fn run() -> eyre::Result<()> {
         Command::Upgrade(args) => {
             deploy::upgrade_program(args, config).await?;
         }
+        Command::WriteBuffer(args) => {
+            deploy::write_buffer(args, config).await?;
+        }
+        Command::VerifyProgram(args) => {
+            program::verify_program(args, &config).await?;
+        }
+        Command::Drift(args) => {
+            drift::drift(args, &config).await?;
+        }
 
         Command::Send(args) => {
             let key_path = args
@@ -296,6 +372,7 @@ async fn run() -> eyre::Result<()> {
             let send_args = SendArgs {
                 fee_payer,
                 signers: args.signer_keys,
+                verification_parallelism: args.verification_parallelism,
             };
 
             let transactions =
@@ -349,10 +426,34 @@ async fn run() -> eyre::Result<()> {
             QueryInstructionSubcommand::Its(command) => {
                 its::query(command, &config)?;
             }
+            QueryInstructionSubcommand::Multicall(command) => {
+                multicall::query(command, &config).await?;
+            }
+            QueryInstructionSubcommand::Events(command) => {
+                events::query(command, &config)?;
+            }
+            QueryInstructionSubcommand::Program(command) => {
+                program::query(command, &config).await?;
+            }
         },
+        Command::Stack(command) => {
+            stack::handle_command(command, &config).await?;
+        }
+        Command::Artifacts(command) => {
+            artifact::handle_command(command, &config)?;
+        }
+        Command::Gateway(gateway::ServiceCommands::Relay(args)) => {
+            relay::relay(args, &config).await?;
+        }
+        Command::Reclaim(command) => {
+            reclaim::handle_command(command, &config).await?;
+        }
         Command::LoadTest(command) => {
             load_test::handle_command(command, &config).await?;
         }
+        Command::Localnet(command) => {
+            localnet::handle_command(command, &config)?;
+        }
     }
     Ok(())
 }
@@ -377,5 +478,11 @@ async fn build_transaction(
             operators::build_transaction(fee_payer, command, config)
         }
         InstructionSubcommand::Memo(command) => memo::build_transaction(fee_payer, command, config),
+        InstructionSubcommand::Multicall(command) => {
+            multicall::build_transaction(fee_payer, command, config)
+        }
+        InstructionSubcommand::Program(command) => {
+            program::build_transaction(fee_payer, command, config).await
+        }
     }
 }
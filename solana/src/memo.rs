@@ -59,6 +59,7 @@ pub(crate) fn build_transaction(
             nonce_account: None,
             nonce_authority: None,
             blockhash_for_message: blockhash.to_string(),
+            pending_update: None,
         };
 
         let serializable_tx = SerializableSolanaTransaction::new(transaction, params);
@@ -8,12 +8,17 @@ use solana_sdk::instruction::AccountMeta;
 use solana_sdk::pubkey::Pubkey;
 
 use crate::Config;
+use crate::governance;
 
 /// Commands for miscellaneous utilities
 #[derive(Subcommand, Debug)]
 pub(crate) enum Commands {
     /// Build an solana_axelar_gateway::executable message
     BuildAxelarMessage(BuildAxelarMessageArgs),
+
+    /// Build an InterchainGovernance proposal upgrading a program from a buffer, and the GMP
+    /// payload to schedule it from the governance chain
+    GovernanceUpgradeProposal(governance::UpgradeProposalArgs),
 }
 
 #[derive(Args, Debug)]
@@ -32,9 +37,10 @@ pub(crate) struct BuildAxelarMessageArgs {
 }
 
 /// Build a message for miscellaneous utilities
-pub(crate) fn do_misc(args: Commands, _config: &Config) -> Result<()> {
+pub(crate) fn do_misc(args: Commands, config: &Config) -> Result<()> {
     match args {
         Commands::BuildAxelarMessage(args) => build_axelar_message(args),
+        Commands::GovernanceUpgradeProposal(args) => governance::upgrade_proposal(args, config),
     }
 }
 
@@ -0,0 +1,298 @@
+use std::path::{Path, PathBuf};
+
+use borsh::BorshSerialize;
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction as SolanaTransaction;
+
+use crate::config::Config;
+use crate::program_loader;
+use crate::types::{
+    SerializableInstruction, SerializableSolanaTransaction, SolanaTransactionParams,
+};
+use crate::utils::{
+    MULTICALL_KEY, fetch_latest_blockhash, get_program_upgrade_authority, get_program_version,
+    read_json_file_from_path, try_infer_program_id_from_env,
+};
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Commands {
+    /// Execute a list of instructions atomically through the Multicall program
+    Call(CallArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum QueryCommands {
+    /// Show the deployment state of the Multicall program
+    Info,
+
+    /// Encode a list of instructions into a Multicall payload without building a transaction
+    EncodePayload(EncodePayloadArgs),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct CallArgs {
+    /// Path to a JSON file with the list of inner instructions, each as
+    /// `{"program_id": "...", "accounts": [{"pubkey": "...", "is_signer": false, "is_writable": true}], "data": "<hex>"}`
+    #[clap(long)]
+    instructions: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct EncodePayloadArgs {
+    /// Path to a JSON file with the list of inner instructions (same format as `multicall call`)
+    #[clap(long)]
+    instructions: PathBuf,
+}
+
+/// One inner call of a multicall. Its program and accounts are referenced by index into the
+/// accounts passed to the Multicall program.
+#[derive(BorshSerialize, Debug)]
+struct ProgramPayload {
+    instruction_data: Vec<u8>,
+    program_account_index: u64,
+    accounts_start_index: u64,
+    accounts_end_index: u64,
+}
+
+#[derive(BorshSerialize, Debug)]
+struct MultiCallPayload {
+    payloads: Vec<ProgramPayload>,
+}
+
+pub(crate) fn build_transaction(
+    fee_payer: &Pubkey,
+    command: Commands,
+    config: &Config,
+) -> eyre::Result<Vec<SerializableSolanaTransaction>> {
+    let instructions = match command {
+        Commands::Call(call_args) => call(call_args, config)?,
+    };
+
+    let blockhash = fetch_latest_blockhash(&config.url)?;
+    let mut serializable_transactions = Vec::with_capacity(instructions.len());
+
+    for instruction in instructions {
+        let message = solana_sdk::message::Message::new_with_blockhash(
+            &[instruction],
+            Some(fee_payer),
+            &blockhash,
+        );
+        let transaction = SolanaTransaction::new_unsigned(message);
+
+        let params = SolanaTransactionParams {
+            fee_payer: fee_payer.to_string(),
+            recent_blockhash: Some(blockhash.to_string()),
+            nonce_account: None,
+            nonce_authority: None,
+            blockhash_for_message: blockhash.to_string(),
+            pending_update: None,
+        };
+
+        let serializable_tx = SerializableSolanaTransaction::new(transaction, params);
+        serializable_transactions.push(serializable_tx);
+    }
+
+    Ok(serializable_transactions)
+}
+
+fn load_inner_instructions(path: &Path) -> eyre::Result<Vec<Instruction>> {
+    let serializable: Vec<SerializableInstruction> = read_json_file_from_path(path)?;
+    if serializable.is_empty() {
+        eyre::bail!("No instructions found in {}", path.display());
+    }
+
+    serializable.iter().map(Instruction::try_from).collect()
+}
+
+/// Flatten the inner instructions into the accounts passed to the Multicall program (each program
+/// followed by its accounts) and the payload referencing them.
+fn encode_multicall(instructions: &[Instruction]) -> eyre::Result<(Vec<u8>, Vec<AccountMeta>)> {
+    let mut accounts = Vec::new();
+    let mut payloads = Vec::with_capacity(instructions.len());
+
+    for instruction in instructions {
+        let program_account_index = u64::try_from(accounts.len())?;
+        accounts.push(AccountMeta::new_readonly(instruction.program_id, false));
+        let accounts_start_index = u64::try_from(accounts.len())?;
+        accounts.extend(instruction.accounts.iter().cloned());
+        let accounts_end_index = u64::try_from(accounts.len())?;
+
+        payloads.push(ProgramPayload {
+            instruction_data: instruction.data.clone(),
+            program_account_index,
+            accounts_start_index,
+            accounts_end_index,
+        });
+    }
+
+    let payload = borsh::to_vec(&MultiCallPayload { payloads })?;
+    Ok((payload, accounts))
+}
+
+/// Anchor instruction discriminator of the Multicall `multicall` instruction.
+fn multicall_discriminator() -> [u8; 8] {
+    let hash = Sha256::digest(b"global:multicall");
+    let mut discriminator = [0_u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+fn multicall_program_id(config: &Config) -> eyre::Result<Pubkey> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    try_infer_program_id_from_env(&env, &config.chain, MULTICALL_KEY)
+}
+
+fn call(call_args: CallArgs, config: &Config) -> eyre::Result<Vec<Instruction>> {
+    let program_id = multicall_program_id(config)?;
+    let inner_instructions = load_inner_instructions(&call_args.instructions)?;
+    let (payload, accounts) = encode_multicall(&inner_instructions)?;
+
+    let mut data = multicall_discriminator().to_vec();
+    data.extend(payload);
+
+    println!("------------------------------------------");
+    println!("Multicall ({program_id}) details:");
+    println!("   Inner instructions: {}", inner_instructions.len());
+    for (i, instruction) in inner_instructions.iter().enumerate() {
+        println!(
+            "   {}. {} ({} accounts, {} bytes of data)",
+            i.saturating_add(1),
+            instruction.program_id,
+            instruction.accounts.len(),
+            instruction.data.len()
+        );
+    }
+    println!("------------------------------------------");
+
+    Ok(vec![Instruction {
+        program_id,
+        accounts,
+        data,
+    }])
+}
+
+pub(crate) async fn query(command: QueryCommands, config: &Config) -> eyre::Result<()> {
+    match command {
+        QueryCommands::Info => info(config).await,
+        QueryCommands::EncodePayload(args) => encode_payload(&args),
+    }
+}
+
+async fn info(config: &Config) -> eyre::Result<()> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let program_id = try_infer_program_id_from_env(&env, &config.chain, MULTICALL_KEY)?;
+
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let deployed = program_loader::fetch_deployed_program(&rpc_client, &program_id).await?;
+
+    println!("------------------------------------------");
+    println!("Multicall program: {program_id}");
+    println!("   Program data account: {}", deployed.programdata_address);
+    println!("   Last deployed at slot: {}", deployed.slot);
+    println!(
+        "   Upgrade authority: {}",
+        deployed
+            .upgrade_authority
+            .map_or_else(|| "none (immutable)".to_owned(), |a| a.to_string())
+    );
+    println!(
+        "   Program size: {} bytes",
+        program_loader::strip_padding(&deployed.data).len()
+    );
+    if let Some(version) = get_program_version(&env, &config.chain, MULTICALL_KEY) {
+        println!("   Version (chains info): {version}");
+    }
+    if let Some(authority) = get_program_upgrade_authority(&env, &config.chain, MULTICALL_KEY) {
+        println!("   Upgrade authority (chains info): {authority}");
+    }
+    println!("------------------------------------------");
+
+    Ok(())
+}
+
+fn encode_payload(args: &EncodePayloadArgs) -> eyre::Result<()> {
+    let inner_instructions = load_inner_instructions(&args.instructions)?;
+    let (payload, accounts) = encode_multicall(&inner_instructions)?;
+
+    println!("Payload: 0x{}", hex::encode(&payload));
+    println!("Accounts:");
+    for account in &accounts {
+        println!(
+            "   {}{}{}",
+            account.pubkey,
+            if account.is_signer { " (signer)" } else { "" },
+            if account.is_writable {
+                " (writable)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multicall_discriminator_matches_anchor() {
+        assert_eq!(
+            multicall_discriminator(),
+            [186, 102, 150, 2, 173, 55, 147, 44]
+        );
+    }
+
+    #[test]
+    fn encode_multicall_fixture() {
+        let first_program = Pubkey::new_from_array([1; 32]);
+        let second_program = Pubkey::new_from_array([2; 32]);
+        let account = AccountMeta::new(Pubkey::new_from_array([3; 32]), true);
+        let instructions = [
+            Instruction {
+                program_id: first_program,
+                accounts: vec![account.clone()],
+                data: vec![0xaa, 0xbb],
+            },
+            Instruction {
+                program_id: second_program,
+                accounts: vec![],
+                data: vec![],
+            },
+        ];
+
+        let (payload, accounts) = encode_multicall(&instructions).unwrap();
+
+        // MultiCallPayload { payloads: Vec<ProgramPayload> } as encoded by the Multicall program:
+        // u32 length prefixes for the vectors and u64 account indices.
+        let expected: Vec<u8> = [
+            &[2, 0, 0, 0][..],
+            &[2, 0, 0, 0, 0xaa, 0xbb],
+            &[0, 0, 0, 0, 0, 0, 0, 0],
+            &[1, 0, 0, 0, 0, 0, 0, 0],
+            &[2, 0, 0, 0, 0, 0, 0, 0],
+            &[0, 0, 0, 0],
+            &[2, 0, 0, 0, 0, 0, 0, 0],
+            &[3, 0, 0, 0, 0, 0, 0, 0],
+            &[3, 0, 0, 0, 0, 0, 0, 0],
+        ]
+        .concat();
+        assert_eq!(payload, expected);
+        assert_eq!(
+            accounts,
+            vec![
+                AccountMeta::new_readonly(first_program, false),
+                account,
+                AccountMeta::new_readonly(second_program, false),
+            ]
+        );
+    }
+}
@@ -92,6 +92,7 @@ pub(crate) struct VerifierSetResponse {
 #[serde(rename_all = "snake_case")]
 pub(crate) enum QueryMsg {
     CurrentVerifierSet,
+    NextVerifierSet,
     Proof { multisig_session_id: u64 },
 }
 
@@ -22,7 +22,7 @@ pub(crate) enum Commands {
 #[derive(Parser, Debug)]
 pub(crate) struct InitArgs {
     #[clap(short, long)]
-    owner: Pubkey,
+    pub(crate) owner: Pubkey,
 }
 
 #[derive(Parser, Debug)]
@@ -68,6 +68,7 @@ pub(crate) fn build_transaction(
             nonce_account: None,
             nonce_authority: None,
             blockhash_for_message: blockhash.to_string(),
+            pending_update: None,
         };
 
         let serializable_tx = SerializableSolanaTransaction::new(transaction, params);
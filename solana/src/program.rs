@@ -0,0 +1,569 @@
+use std::io::Write as _;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use eyre::eyre;
+use serde_json::Value;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction as SolanaTransaction;
+
+use crate::artifact::{self, ArtifactArgs};
+use crate::config::Config;
+use crate::deploy::{confirm_action_with_message, program_key_from_program};
+use crate::program_loader::{self, LoaderAccount};
+use crate::types::{
+    PendingProgramUpdate, Programs, SerializableSolanaTransaction, SolanaTransactionParams,
+};
+use crate::utils::{
+    fetch_latest_blockhash, get_program_upgrade_authority, get_program_version,
+    read_json_file_from_path, set_program_artifact_hash, set_program_upgrade_authority,
+    set_program_version, try_infer_program_id_from_env, write_json_to_file_path,
+};
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Commands {
+    /// Upgrade a program from a buffer staged with `write-buffer`. Meant to be used with
+    /// `generate` when the upgrade authority is a cold key or a multisig.
+    Upgrade(UpgradeArgs),
+
+    /// Transfer the upgrade authority of a program, e.g. to the InterchainGovernance PDA or a
+    /// Squads vault
+    SetUpgradeAuthority(SetUpgradeAuthorityArgs),
+
+    /// Remove the upgrade authority of a program. This cannot be undone.
+    MakeImmutable(MakeImmutableArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum QueryCommands {
+    /// Compare the on-chain upgrade authority of programs with the chains info file
+    UpgradeAuthority(UpgradeAuthorityQueryArgs),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct UpgradeArgs {
+    /// Name of the program to upgrade
+    #[clap(long, value_enum)]
+    program: Programs,
+
+    /// Buffer account holding the new program, owned by the upgrade authority
+    #[clap(long)]
+    buffer: Pubkey,
+
+    /// Upgrade authority of the program. Defaults to the upgrade authority in the chains info
+    /// file.
+    #[clap(long)]
+    upgrade_authority: Option<Pubkey>,
+
+    /// Account receiving the buffer's lamports. Defaults to the fee payer.
+    #[clap(long)]
+    spill: Option<Pubkey>,
+
+    /// Version of the new program, recorded in the chains info file once the upgrade transaction
+    /// has been broadcast
+    #[clap(long)]
+    version: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct SetUpgradeAuthorityArgs {
+    /// Name of the program
+    #[clap(long, value_enum)]
+    program: Programs,
+
+    /// New upgrade authority (e.g. a Squads vault)
+    #[clap(
+        long,
+        required_unless_present = "governance",
+        conflicts_with = "governance"
+    )]
+    new_authority: Option<Pubkey>,
+
+    /// Transfer the upgrade authority to the InterchainGovernance config PDA. Implies
+    /// --skip-new-authority-signature.
+    #[clap(long)]
+    governance: bool,
+
+    /// Current upgrade authority. Defaults to the upgrade authority in the chains info file.
+    #[clap(long)]
+    current_authority: Option<Pubkey>,
+
+    /// Do not require the new authority to sign. Needed when the new authority is a PDA (such as
+    /// the governance PDA or a Squads vault) and therefore cannot sign the transaction.
+    #[clap(long)]
+    skip_new_authority_signature: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct MakeImmutableArgs {
+    /// Name of the program
+    #[clap(long, value_enum)]
+    program: Programs,
+
+    /// Current upgrade authority. Defaults to the upgrade authority in the chains info file.
+    #[clap(long)]
+    current_authority: Option<Pubkey>,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct UpgradeAuthorityQueryArgs {
+    /// Program to check. Defaults to every program in the chains info file.
+    #[clap(long, value_enum)]
+    program: Option<Programs>,
+}
+
+pub(crate) async fn build_transaction(
+    fee_payer: &Pubkey,
+    command: Commands,
+    config: &Config,
+) -> eyre::Result<Vec<SerializableSolanaTransaction>> {
+    let (instructions, pending_update) = match command {
+        Commands::Upgrade(upgrade_args) => upgrade(fee_payer, upgrade_args, config).await?,
+        Commands::SetUpgradeAuthority(set_upgrade_authority_args) => {
+            set_upgrade_authority(set_upgrade_authority_args, config).await?
+        }
+        Commands::MakeImmutable(make_immutable_args) => {
+            make_immutable(make_immutable_args, config).await?
+        }
+    };
+
+    // The instructions of an upgrade (extension of the program data and the upgrade itself)
+    // must land together, so they are kept in a single transaction.
+    let blockhash = fetch_latest_blockhash(&config.url)?;
+    let message = solana_sdk::message::Message::new_with_blockhash(
+        &instructions,
+        Some(fee_payer),
+        &blockhash,
+    );
+    let transaction = SolanaTransaction::new_unsigned(message);
+    let params = SolanaTransactionParams {
+        fee_payer: fee_payer.to_string(),
+        recent_blockhash: Some(blockhash.to_string()),
+        nonce_account: None,
+        nonce_authority: None,
+        blockhash_for_message: blockhash.to_string(),
+        pending_update,
+    };
+
+    Ok(vec![SerializableSolanaTransaction::new(
+        transaction,
+        params,
+    )])
+}
+
+async fn upgrade(
+    fee_payer: &Pubkey,
+    upgrade_args: UpgradeArgs,
+    config: &Config,
+) -> eyre::Result<(
+    Vec<solana_sdk::instruction::Instruction>,
+    Option<PendingProgramUpdate>,
+)> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let program_key = program_key_from_program(&upgrade_args.program);
+    let program_id = try_infer_program_id_from_env(&env, &config.chain, program_key)?;
+    let upgrade_authority = upgrade_args
+        .upgrade_authority
+        .or_else(|| get_program_upgrade_authority(&env, &config.chain, program_key))
+        .ok_or_else(|| {
+            eyre!("No --upgrade-authority provided and none found for {program_key} in the chains info file")
+        })?;
+
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let Some(LoaderAccount::Buffer { authority, data }) =
+        program_loader::fetch_loader_account(&rpc_client, &upgrade_args.buffer).await?
+    else {
+        eyre::bail!("Account {} is not a program buffer", upgrade_args.buffer);
+    };
+
+    if authority != Some(upgrade_authority) {
+        eyre::bail!(
+            "Buffer {} is owned by {}, not by the upgrade authority {upgrade_authority}",
+            upgrade_args.buffer,
+            authority.map_or_else(|| "nobody".to_owned(), |a| a.to_string())
+        );
+    }
+
+    let artifact_hash = artifact::sha256_hex(&data);
+    let instructions = program_loader::upgrade_instructions(
+        &rpc_client,
+        &program_id,
+        &upgrade_args.buffer,
+        &upgrade_authority,
+        fee_payer,
+        &upgrade_args.spill.unwrap_or(*fee_payer),
+        data.len(),
+    )
+    .await?;
+
+    println!("------------------------------------------");
+    println!("Program upgrade details:");
+    println!("   Program: {:?} ({program_id})", upgrade_args.program);
+    println!("   Buffer: {}", upgrade_args.buffer);
+    println!("   Upgrade authority: {upgrade_authority}");
+    println!("   Program SHA-256: {artifact_hash}");
+    if let Some(current) = get_program_version(&env, &config.chain, program_key) {
+        println!("   Current version: {current}");
+    }
+    if let Some(version) = &upgrade_args.version {
+        println!("   New version: {version} (recorded once the upgrade is broadcast)");
+    }
+    println!("------------------------------------------");
+
+    let pending_update = PendingProgramUpdate {
+        program_key: program_key.to_owned(),
+        artifact_hash: Some(artifact_hash),
+        version: upgrade_args.version,
+        upgrade_authority: None,
+        immutable: false,
+    };
+
+    Ok((instructions, Some(pending_update)))
+}
+
+/// Resolve the program and its current upgrade authority, making sure the latter matches the
+/// on-chain state.
+async fn current_upgrade_authority(
+    program: &Programs,
+    current_authority: Option<Pubkey>,
+    config: &Config,
+) -> eyre::Result<(&'static str, Pubkey, Pubkey)> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let program_key = program_key_from_program(program);
+    let program_id = try_infer_program_id_from_env(&env, &config.chain, program_key)?;
+    let current_authority = current_authority
+        .or_else(|| get_program_upgrade_authority(&env, &config.chain, program_key))
+        .ok_or_else(|| {
+            eyre!("No --current-authority provided and none found for {program_key} in the chains info file")
+        })?;
+
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let deployed = program_loader::fetch_deployed_program(&rpc_client, &program_id).await?;
+    match deployed.upgrade_authority {
+        Some(authority) if authority == current_authority => {}
+        Some(authority) => eyre::bail!(
+            "The on-chain upgrade authority of {program_key} is {authority}, not {current_authority}"
+        ),
+        None => eyre::bail!("{program_key} ({program_id}) is already immutable"),
+    }
+
+    Ok((program_key, program_id, current_authority))
+}
+
+async fn set_upgrade_authority(
+    set_upgrade_authority_args: SetUpgradeAuthorityArgs,
+    config: &Config,
+) -> eyre::Result<(
+    Vec<solana_sdk::instruction::Instruction>,
+    Option<PendingProgramUpdate>,
+)> {
+    let (program_key, program_id, current_authority) = current_upgrade_authority(
+        &set_upgrade_authority_args.program,
+        set_upgrade_authority_args.current_authority,
+        config,
+    )
+    .await?;
+
+    let new_authority = match set_upgrade_authority_args.new_authority {
+        Some(new_authority) => new_authority,
+        None => solana_axelar_governance::GovernanceConfig::find_pda().0,
+    };
+    if new_authority == current_authority {
+        eyre::bail!("{new_authority} is already the upgrade authority of {program_key}");
+    }
+
+    // A PDA has no private key, so it can only receive the authority without signing for it.
+    let checked = !(set_upgrade_authority_args.skip_new_authority_signature
+        || set_upgrade_authority_args.governance);
+    if checked && !new_authority.is_on_curve() {
+        eyre::bail!(
+            "{new_authority} is a PDA and cannot sign. Use --skip-new-authority-signature to transfer the authority to it."
+        );
+    }
+
+    let instruction = if checked {
+        solana_loader_v3_interface::instruction::set_upgrade_authority_checked(
+            &program_id,
+            &current_authority,
+            &new_authority,
+        )
+    } else {
+        solana_loader_v3_interface::instruction::set_upgrade_authority(
+            &program_id,
+            &current_authority,
+            Some(&new_authority),
+        )
+    };
+
+    println!("------------------------------------------");
+    println!("Upgrade authority transfer details:");
+    println!(
+        "   Program: {:?} ({program_id})",
+        set_upgrade_authority_args.program
+    );
+    println!("   Current authority: {current_authority}");
+    println!("   New authority: {new_authority}");
+    if checked {
+        println!("   The new authority must sign the transaction as well.");
+    }
+    println!("------------------------------------------");
+
+    let pending_update = PendingProgramUpdate {
+        program_key: program_key.to_owned(),
+        artifact_hash: None,
+        version: None,
+        upgrade_authority: Some(new_authority.to_string()),
+        immutable: false,
+    };
+
+    Ok((vec![instruction], Some(pending_update)))
+}
+
+async fn make_immutable(
+    make_immutable_args: MakeImmutableArgs,
+    config: &Config,
+) -> eyre::Result<(
+    Vec<solana_sdk::instruction::Instruction>,
+    Option<PendingProgramUpdate>,
+)> {
+    let (program_key, program_id, current_authority) = current_upgrade_authority(
+        &make_immutable_args.program,
+        make_immutable_args.current_authority,
+        config,
+    )
+    .await?;
+
+    println!("------------------------------------------");
+    println!(
+        "\u{26a0}\u{fe0f} Making {program_key} ({program_id}) immutable. It will never be upgradeable again."
+    );
+    println!("------------------------------------------");
+
+    if !confirm_action_with_message("Remove the upgrade authority permanently?")? {
+        eyre::bail!("Aborted");
+    }
+    print!("Type the program ID ({program_id}) to confirm: ");
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    if input.trim() != program_id.to_string() {
+        eyre::bail!("Program ID does not match, aborted");
+    }
+
+    let instruction = solana_loader_v3_interface::instruction::set_upgrade_authority(
+        &program_id,
+        &current_authority,
+        None,
+    );
+
+    let pending_update = PendingProgramUpdate {
+        program_key: program_key.to_owned(),
+        artifact_hash: None,
+        version: None,
+        upgrade_authority: None,
+        immutable: true,
+    };
+
+    Ok((vec![instruction], Some(pending_update)))
+}
+
+pub(crate) async fn query(command: QueryCommands, config: &Config) -> eyre::Result<()> {
+    match command {
+        QueryCommands::UpgradeAuthority(args) => query_upgrade_authority(args, config).await,
+    }
+}
+
+async fn query_upgrade_authority(
+    args: UpgradeAuthorityQueryArgs,
+    config: &Config,
+) -> eyre::Result<()> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let programs = match args.program {
+        Some(program) => vec![program],
+        None => Programs::value_variants()
+            .iter()
+            .filter(|program| {
+                try_infer_program_id_from_env(
+                    &env,
+                    &config.chain,
+                    program_key_from_program(program),
+                )
+                .is_ok()
+            })
+            .cloned()
+            .collect(),
+    };
+
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let mut mismatches = 0_usize;
+
+    for program in &programs {
+        let program_key = program_key_from_program(program);
+        let program_id = try_infer_program_id_from_env(&env, &config.chain, program_key)?;
+        let recorded = get_program_upgrade_authority(&env, &config.chain, program_key);
+        let onchain = program_loader::fetch_deployed_program(&rpc_client, &program_id)
+            .await?
+            .upgrade_authority;
+
+        let display = |authority: Option<Pubkey>| {
+            authority.map_or_else(|| "none (immutable)".to_owned(), |a| a.to_string())
+        };
+        let status = if recorded == onchain {
+            "\u{2705}"
+        } else {
+            mismatches = mismatches.saturating_add(1);
+            "\u{274c}"
+        };
+
+        println!("{status} {program_key} ({program_id})");
+        println!("   On-chain:    {}", display(onchain));
+        println!("   Chains info: {}", display(recorded));
+    }
+
+    if mismatches > 0 {
+        eyre::bail!("{mismatches} program(s) have an upgrade authority differing from chains info");
+    }
+
+    Ok(())
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct VerifyProgramArgs {
+    /// Name of the program to verify
+    #[clap(long, value_enum)]
+    program: Programs,
+
+    /// Address of the program. Defaults to the address in the chains info file.
+    #[clap(long)]
+    program_id: Option<Pubkey>,
+
+    /// Artifact to compare against. Defaults to the version recorded in the chains info file.
+    #[clap(flatten)]
+    artifact: ArtifactArgs,
+}
+
+/// Check that the executable deployed on-chain matches a release artifact or local build.
+pub(crate) async fn verify_program(args: VerifyProgramArgs, config: &Config) -> eyre::Result<()> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let program_key = program_key_from_program(&args.program);
+    let program_id = match args.program_id {
+        Some(program_id) => program_id,
+        None => try_infer_program_id_from_env(&env, &config.chain, program_key)?,
+    };
+
+    let mut artifact_args = args.artifact;
+    if artifact_args.program_path.is_none()
+        && artifact_args.version.is_none()
+        && artifact_args.artifact_dir.is_none()
+    {
+        let version = get_program_version(&env, &config.chain, program_key).ok_or_else(|| {
+            eyre!("No artifact given and no version recorded for {program_key} in the chains info file")
+        })?;
+        println!("Comparing against version {version} from the chains info file");
+        artifact_args.version = Some(version);
+    }
+
+    let program_path =
+        artifact::resolve_program_path(&args.program, &artifact_args, config).await?;
+    let artifact_data = program_loader::read_program_file(&program_path)?;
+
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let deployed = program_loader::fetch_deployed_program(&rpc_client, &program_id).await?;
+
+    let onchain_executable = program_loader::strip_padding(&deployed.data);
+    let artifact_executable = program_loader::strip_padding(&artifact_data);
+    let onchain_hash = artifact::sha256_hex(onchain_executable);
+    let artifact_hash = artifact::sha256_hex(artifact_executable);
+    let recorded_authority = get_program_upgrade_authority(&env, &config.chain, program_key);
+
+    println!("------------------------------------------");
+    println!("Program {:?} ({program_id})", args.program);
+    println!("   Program data account: {}", deployed.programdata_address);
+    println!("   Last deployed at slot: {}", deployed.slot);
+    match deployed.upgrade_authority {
+        Some(authority) => println!("   Upgrade authority: {authority}"),
+        None => println!("   Upgrade authority: none (immutable)"),
+    }
+    if recorded_authority.is_some() && recorded_authority != deployed.upgrade_authority {
+        println!(
+            "   \u{26a0}\u{fe0f} Chains info records upgrade authority {}",
+            recorded_authority
+                .map(|a| a.to_string())
+                .unwrap_or_default()
+        );
+    }
+    println!(
+        "   On-chain executable: {} bytes, SHA-256 {onchain_hash}",
+        onchain_executable.len()
+    );
+    println!(
+        "   Artifact ({}): {} bytes, SHA-256 {artifact_hash}",
+        program_path.display(),
+        artifact_executable.len()
+    );
+    println!("------------------------------------------");
+
+    if onchain_hash != artifact_hash {
+        eyre::bail!("\u{274c} Deployed program {program_id} does NOT match the artifact");
+    }
+
+    println!("\u{2705} Deployed program {program_id} matches the artifact");
+    Ok(())
+}
+
+/// Apply the chains info update carried by a transaction that has just landed.
+pub(crate) fn apply_pending_update(
+    config: &Config,
+    params: &SolanaTransactionParams,
+) -> eyre::Result<()> {
+    let Some(pending_update) = &params.pending_update else {
+        return Ok(());
+    };
+
+    let PendingProgramUpdate {
+        program_key,
+        artifact_hash,
+        version,
+        upgrade_authority,
+        immutable,
+    } = pending_update;
+
+    let mut env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    if let Some(artifact_hash) = artifact_hash {
+        set_program_artifact_hash(
+            &mut env,
+            &config.chain,
+            program_key,
+            artifact_hash,
+            version.as_deref(),
+        )?;
+        println!("Updated {program_key} artifact hash to {artifact_hash} in chains info file.");
+    }
+    if let Some(version) = version {
+        set_program_version(&mut env, &config.chain, program_key, version)?;
+        println!("Updated {program_key} version to {version} in chains info file.");
+    }
+    if let Some(upgrade_authority) = upgrade_authority {
+        set_program_upgrade_authority(
+            &mut env,
+            &config.chain,
+            program_key,
+            Some(upgrade_authority),
+        )?;
+        println!(
+            "Updated {program_key} upgrade authority to {upgrade_authority} in chains info file."
+        );
+    }
+    if *immutable {
+        set_program_upgrade_authority(&mut env, &config.chain, program_key, None)?;
+        println!("Marked {program_key} as immutable in chains info file.");
+    }
+    write_json_to_file_path(&env, &config.chains_info_file)?;
+
+    Ok(())
+}
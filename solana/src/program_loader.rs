@@ -0,0 +1,863 @@
+//! Native implementation of the upgradeable BPF loader (loader-v3) flows used to deploy and
+//! upgrade programs: buffer creation, parallel chunked writes, deploy/upgrade from a buffer and
+//! recovery of buffers left behind by interrupted runs.
+
+use std::collections::HashMap;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use eyre::{Result, bail, eyre};
+use futures::StreamExt as _;
+use solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_commitment_config::CommitmentConfig;
+use solana_loader_v3_interface::instruction as loader_instruction;
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, read_keypair_file, write_keypair_file};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+
+/// Maximum size of a serialized transaction (IPv6 MTU minus headers).
+const PACKET_DATA_SIZE: usize = 1280 - 40 - 8;
+
+/// Number of write transactions in flight at the same time.
+const MAX_PARALLEL_WRITES: usize = 32;
+
+/// Number of rounds in which failed or dropped writes are re-signed and resent.
+const MAX_WRITE_ROUNDS: usize = 10;
+
+/// `getSignatureStatuses` accepts at most this many signatures per request.
+const MAX_SIGNATURE_STATUS_BATCH: usize = 256;
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+/// Read a program binary from disk, making sure it looks like an ELF shared object.
+pub(crate) fn read_program_file(path: &Path) -> Result<Vec<u8>> {
+    let data = std::fs::read(path)
+        .map_err(|e| eyre!("Failed to read program file {}: {e}", path.display()))?;
+
+    if !data.starts_with(ELF_MAGIC) {
+        bail!("{} is not an ELF program binary", path.display());
+    }
+
+    Ok(data)
+}
+
+/// On-chain state of a loader-v3 account owned by the upgradeable loader.
+#[derive(Debug, Clone)]
+pub(crate) enum LoaderAccount {
+    Buffer {
+        authority: Option<Pubkey>,
+        data: Vec<u8>,
+    },
+    Program {
+        programdata_address: Pubkey,
+    },
+    ProgramData {
+        slot: u64,
+        upgrade_authority: Option<Pubkey>,
+        data: Vec<u8>,
+    },
+    Uninitialized,
+}
+
+/// Fetch and decode an account owned by the upgradeable loader. Returns `None` if the account
+/// does not exist.
+pub(crate) async fn fetch_loader_account(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+) -> Result<Option<LoaderAccount>> {
+    let Some(account) = rpc_client
+        .get_account_with_commitment(address, rpc_client.commitment())
+        .await?
+        .value
+    else {
+        return Ok(None);
+    };
+
+    if account.owner != solana_sdk_ids::bpf_loader_upgradeable::ID {
+        bail!(
+            "Account {address} is not owned by the upgradeable loader (owner: {})",
+            account.owner
+        );
+    }
+
+    decode_loader_account(&account.data).map(Some)
+}
+
+/// Deployed state of an upgradeable program, read from its program data account.
+#[derive(Debug, Clone)]
+pub(crate) struct DeployedProgram {
+    pub(crate) programdata_address: Pubkey,
+    /// Slot of the last deployment or upgrade
+    pub(crate) slot: u64,
+    pub(crate) upgrade_authority: Option<Pubkey>,
+    /// Program data, including the zero padding up to the maximum program length
+    pub(crate) data: Vec<u8>,
+}
+
+/// Fetch the program data of the upgradeable program at `program_id`.
+pub(crate) async fn fetch_deployed_program(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<DeployedProgram> {
+    let Some(LoaderAccount::Program {
+        programdata_address,
+    }) = fetch_loader_account(rpc_client, program_id).await?
+    else {
+        bail!("{program_id} is not a program deployed with the upgradeable loader");
+    };
+
+    let Some(LoaderAccount::ProgramData {
+        slot,
+        upgrade_authority,
+        data,
+    }) = fetch_loader_account(rpc_client, &programdata_address).await?
+    else {
+        bail!("Program data account {programdata_address} of {program_id} is missing or invalid");
+    };
+
+    Ok(DeployedProgram {
+        programdata_address,
+        slot,
+        upgrade_authority,
+        data,
+    })
+}
+
+/// Strip the zero padding the loader appends to program data up to its maximum length.
+pub(crate) fn strip_padding(data: &[u8]) -> &[u8] {
+    let len = data
+        .iter()
+        .rposition(|b| *b != 0)
+        .map_or(0, |i| i.saturating_add(1));
+    data.get(..len).unwrap_or_default()
+}
+
+/// The program binary as it was deployed, without the zero padding the loader appends to program
+/// data. The length of the binary is taken from its ELF header, since the section header table
+/// ends the file; binaries usually end in zeros themselves, which stripping every trailing zero
+/// would cut off.
+pub(crate) fn deployed_binary(data: &[u8]) -> &[u8] {
+    elf_len(data)
+        .and_then(|len| data.get(..len))
+        .unwrap_or_else(|| strip_padding(data))
+}
+
+/// End of the section header table of a 64-bit little-endian ELF, or `None` if `data` doesn't
+/// start with such a header.
+fn elf_len(data: &[u8]) -> Option<usize> {
+    if data.get(..6)? != b"\x7fELF\x02\x01" {
+        return None;
+    }
+    let section_headers_offset = u64::from_le_bytes(data.get(0x28..0x30)?.try_into().ok()?);
+    let section_header_size = u16::from_le_bytes(data.get(0x3a..0x3c)?.try_into().ok()?);
+    let section_header_count = u16::from_le_bytes(data.get(0x3c..0x3e)?.try_into().ok()?);
+    usize::try_from(section_headers_offset).ok()?.checked_add(
+        usize::from(section_header_size).checked_mul(usize::from(section_header_count))?,
+    )
+}
+
+/// Decode the raw data of an upgradeable loader account.
+pub(crate) fn decode_loader_account(data: &[u8]) -> Result<LoaderAccount> {
+    let (state, _): (UpgradeableLoaderState, usize) =
+        bincode::serde::decode_from_slice(data, bincode::config::legacy())
+            .map_err(|e| eyre!("Failed to decode upgradeable loader state: {e}"))?;
+
+    Ok(match state {
+        UpgradeableLoaderState::Uninitialized => LoaderAccount::Uninitialized,
+        UpgradeableLoaderState::Buffer { authority_address } => LoaderAccount::Buffer {
+            authority: authority_address,
+            data: data
+                .get(UpgradeableLoaderState::size_of_buffer_metadata()..)
+                .unwrap_or_default()
+                .to_vec(),
+        },
+        UpgradeableLoaderState::Program {
+            programdata_address,
+        } => LoaderAccount::Program {
+            programdata_address,
+        },
+        UpgradeableLoaderState::ProgramData {
+            slot,
+            upgrade_authority_address,
+        } => LoaderAccount::ProgramData {
+            slot,
+            upgrade_authority: upgrade_authority_address,
+            data: data
+                .get(UpgradeableLoaderState::size_of_programdata_metadata()..)
+                .unwrap_or_default()
+                .to_vec(),
+        },
+    })
+}
+
+/// Where the keypair of the buffer used for a deployment comes from.
+pub(crate) enum BufferSource {
+    /// A buffer keypair managed by the CLI, persisted in the output directory until the buffer
+    /// has been consumed so that an interrupted run can pick up where it left off.
+    Managed { keypair: Keypair, path: PathBuf },
+    /// A buffer signer provided by the user.
+    Provided(Box<dyn Signer>),
+}
+
+impl BufferSource {
+    /// Load the managed buffer keypair for `name` from `output_dir`, creating and persisting a new
+    /// one if no previous run left one behind.
+    pub(crate) fn managed(output_dir: &Path, name: &str) -> Result<Self> {
+        let path = output_dir.join(format!("{name}-buffer-keypair.json"));
+
+        let keypair = if path.exists() {
+            let keypair = read_keypair_file(&path)
+                .map_err(|e| eyre!("Failed to read buffer keypair {}: {e}", path.display()))?;
+            println!(
+                "Found buffer keypair from a previous run at {}, resuming with buffer {}",
+                path.display(),
+                keypair.pubkey()
+            );
+            keypair
+        } else {
+            let keypair = Keypair::new();
+            write_keypair_file(&keypair, &path)
+                .map_err(|e| eyre!("Failed to save buffer keypair {}: {e}", path.display()))?;
+            keypair
+        };
+
+        Ok(Self::Managed { keypair, path })
+    }
+
+    pub(crate) fn signer(&self) -> &dyn Signer {
+        match self {
+            Self::Managed { keypair, .. } => keypair,
+            Self::Provided(signer) => signer.as_ref(),
+        }
+    }
+
+    pub(crate) fn pubkey(&self) -> Pubkey {
+        self.signer().pubkey()
+    }
+
+    /// Forget the managed keypair once the buffer has been consumed by a deploy or upgrade.
+    pub(crate) fn finish(self) -> Result<()> {
+        if let Self::Managed { path, .. } = self {
+            std::fs::remove_file(&path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Tell the user how to recover from a failure that left the buffer behind.
+    pub(crate) fn print_recovery_hint(&self) {
+        eprintln!("\nThe program buffer {} was left on-chain.", self.pubkey());
+        match self {
+            Self::Managed { path, .. } => eprintln!(
+                "Re-run the same command to resume from it (keypair saved at {}).",
+                path.display()
+            ),
+            Self::Provided(_) => {
+                eprintln!("Re-run the same command with the same --buffer to resume from it.");
+            }
+        }
+    }
+}
+
+/// Write `program_data` to `buffer` using the fee payer as write authority, then hand the buffer
+/// over to `final_authority`. Steps already completed by an interrupted run are skipped.
+pub(crate) async fn stage_buffer(
+    rpc_client: &RpcClient,
+    fee_payer: &dyn Signer,
+    buffer: &dyn Signer,
+    final_authority: &Pubkey,
+    program_data: &[u8],
+) -> Result<()> {
+    if let Some(LoaderAccount::Buffer { authority, data }) =
+        fetch_loader_account(rpc_client, &buffer.pubkey()).await?
+    {
+        if authority == Some(*final_authority) && data == program_data {
+            println!(
+                "Buffer {} already contains the program and is owned by {final_authority}",
+                buffer.pubkey()
+            );
+            return Ok(());
+        }
+    }
+
+    let writer = BufferWriter {
+        rpc_client,
+        fee_payer,
+        write_authority: fee_payer,
+    };
+    writer.write(buffer, program_data).await?;
+
+    if *final_authority != fee_payer.pubkey() {
+        set_buffer_authority(
+            rpc_client,
+            fee_payer,
+            &buffer.pubkey(),
+            fee_payer,
+            final_authority,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Signers taking part in writing a program to a buffer.
+pub(crate) struct BufferWriter<'a> {
+    pub(crate) rpc_client: &'a RpcClient,
+    pub(crate) fee_payer: &'a dyn Signer,
+    /// Authority of the buffer while it is being written.
+    pub(crate) write_authority: &'a dyn Signer,
+}
+
+impl BufferWriter<'_> {
+    /// Make sure `buffer` exists, is large enough for `program_data` and contains it, creating
+    /// the account and writing only the chunks that differ from what is already on-chain.
+    pub(crate) async fn write(&self, buffer: &dyn Signer, program_data: &[u8]) -> Result<()> {
+        let buffer_address = buffer.pubkey();
+        let existing = match fetch_loader_account(self.rpc_client, &buffer_address).await? {
+            None => {
+                self.create_buffer(buffer, program_data.len()).await?;
+                Vec::new()
+            }
+            Some(LoaderAccount::Buffer { authority, data }) => {
+                if authority != Some(self.write_authority.pubkey()) {
+                    bail!(
+                        "Buffer {buffer_address} authority is {}, expected {}",
+                        authority.map_or_else(|| "none".to_owned(), |a| a.to_string()),
+                        self.write_authority.pubkey()
+                    );
+                }
+                if data.len() != program_data.len() {
+                    bail!(
+                        "Buffer {buffer_address} holds {} bytes but the program is {} bytes. Close it and retry.",
+                        data.len(),
+                        program_data.len()
+                    );
+                }
+                println!("Reusing existing buffer {buffer_address}");
+                data
+            }
+            Some(other) => bail!("Account {buffer_address} is not a buffer: {other:?}"),
+        };
+
+        self.write_chunks(&buffer_address, program_data, &existing)
+            .await
+    }
+
+    async fn create_buffer(&self, buffer: &dyn Signer, program_len: usize) -> Result<()> {
+        let lamports = self
+            .rpc_client
+            .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_buffer(
+                program_len,
+            ))
+            .await?;
+
+        println!(
+            "Creating buffer {} ({program_len} bytes, {} SOL)",
+            buffer.pubkey(),
+            format_sol(lamports)
+        );
+
+        let instructions = loader_instruction::create_buffer(
+            &self.fee_payer.pubkey(),
+            &buffer.pubkey(),
+            &self.write_authority.pubkey(),
+            lamports,
+            program_len,
+        )?;
+
+        send_and_confirm(
+            self.rpc_client,
+            &instructions,
+            &[self.fee_payer, buffer],
+            self.fee_payer,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn write_chunks(
+        &self,
+        buffer_address: &Pubkey,
+        program_data: &[u8],
+        existing: &[u8],
+    ) -> Result<()> {
+        let chunk_size = max_write_chunk_size(
+            buffer_address,
+            &self.fee_payer.pubkey(),
+            &self.write_authority.pubkey(),
+        )?;
+
+        let mut pending: Vec<(u32, &[u8])> = Vec::new();
+        for (index, chunk) in program_data.chunks(chunk_size).enumerate() {
+            let offset = index * chunk_size;
+            if existing.get(offset..offset + chunk.len()) != Some(chunk) {
+                pending.push((u32::try_from(offset)?, chunk));
+            }
+        }
+
+        let total_chunks = program_data.chunks(chunk_size).len();
+        if pending.is_empty() {
+            println!("Buffer {buffer_address} already contains the program");
+            return Ok(());
+        }
+
+        let mut progress = Progress::new("Writing buffer", total_chunks);
+        progress.advance(total_chunks - pending.len());
+
+        for round in 1..=MAX_WRITE_ROUNDS {
+            let blockhash = self.rpc_client.get_latest_blockhash().await?;
+            let mut in_flight: HashMap<Signature, (u32, &[u8])> = HashMap::new();
+
+            let transactions = pending
+                .iter()
+                .map(|(offset, bytes)| {
+                    let instruction = loader_instruction::write(
+                        buffer_address,
+                        &self.write_authority.pubkey(),
+                        *offset,
+                        bytes.to_vec(),
+                    );
+                    let transaction = sign_transaction(
+                        &[instruction],
+                        &[self.fee_payer, self.write_authority],
+                        self.fee_payer,
+                        blockhash,
+                    )?;
+                    in_flight.insert(transaction.signatures[0], (*offset, bytes));
+                    Ok(transaction)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let send_config = RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..RpcSendTransactionConfig::default()
+            };
+            futures::stream::iter(transactions.iter())
+                .map(|transaction| {
+                    self.rpc_client
+                        .send_transaction_with_config(transaction, send_config)
+                })
+                .buffer_unordered(MAX_PARALLEL_WRITES)
+                .for_each(|_| async {})
+                .await;
+
+            let mut failed = Vec::new();
+            let confirmed = confirm_signatures(
+                self.rpc_client,
+                &mut in_flight,
+                &mut failed,
+                &blockhash,
+                &mut progress,
+            )
+            .await?;
+            if confirmed == pending.len() {
+                progress.finish();
+                return Ok(());
+            }
+
+            pending = in_flight.into_values().chain(failed).collect();
+            pending.sort_unstable_by_key(|(offset, _)| *offset);
+            progress.note(&format!(
+                "{} chunk(s) failed or expired in round {round}, resending",
+                pending.len()
+            ));
+        }
+
+        progress.finish();
+        bail!(
+            "Failed to write {} chunk(s) to buffer {buffer_address} after {MAX_WRITE_ROUNDS} rounds",
+            pending.len()
+        )
+    }
+}
+
+/// Poll the statuses of the in-flight writes until all are confirmed or failed, or the blockhash
+/// used to sign them has expired. Confirmed writes are removed from `in_flight` and failed ones
+/// are moved to `failed`; both the failed and the remaining entries need to be resent. Returns the
+/// number of confirmed writes.
+async fn confirm_signatures<'a>(
+    rpc_client: &RpcClient,
+    in_flight: &mut HashMap<Signature, (u32, &'a [u8])>,
+    failed: &mut Vec<(u32, &'a [u8])>,
+    blockhash: &Hash,
+    progress: &mut Progress,
+) -> Result<usize> {
+    let mut confirmed = 0;
+
+    while !in_flight.is_empty() {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let signatures: Vec<Signature> = in_flight.keys().copied().collect();
+        for batch in signatures.chunks(MAX_SIGNATURE_STATUS_BATCH) {
+            let statuses = rpc_client.get_signature_statuses(batch).await?.value;
+            for (signature, status) in batch.iter().zip(statuses) {
+                let Some(status) = status else {
+                    continue;
+                };
+
+                if let Some(err) = status.err {
+                    progress.note(&format!("Write {signature} failed: {err}"));
+                    failed.extend(in_flight.remove(signature));
+                    continue;
+                }
+
+                if status.satisfies_commitment(rpc_client.commitment()) {
+                    in_flight.remove(signature);
+                    confirmed += 1;
+                    progress.advance(1);
+                }
+            }
+        }
+
+        if !rpc_client
+            .is_blockhash_valid(blockhash, CommitmentConfig::processed())
+            .await?
+        {
+            break;
+        }
+    }
+
+    Ok(confirmed)
+}
+
+/// Largest number of program bytes that fit in a single `Write` transaction.
+fn max_write_chunk_size(
+    buffer_address: &Pubkey,
+    fee_payer: &Pubkey,
+    authority: &Pubkey,
+) -> Result<usize> {
+    let empty_write = loader_instruction::write(buffer_address, authority, 0, Vec::new());
+    let message = Message::new_with_blockhash(&[empty_write], Some(fee_payer), &Hash::default());
+    let transaction = Transaction::new_unsigned(message);
+    let empty_size = bincode::serde::encode_to_vec(&transaction, bincode::config::legacy())?.len();
+
+    // The length prefix of the bytes vector grows by up to two bytes once it holds data.
+    PACKET_DATA_SIZE
+        .checked_sub(empty_size + 2)
+        .filter(|size| *size > 0)
+        .ok_or_else(|| eyre!("Write transaction does not fit in a packet"))
+}
+
+/// Deploy a program from a fully written buffer. The buffer authority must be the upgrade
+/// authority.
+pub(crate) async fn deploy_from_buffer(
+    rpc_client: &RpcClient,
+    fee_payer: &dyn Signer,
+    program: &dyn Signer,
+    buffer: &Pubkey,
+    upgrade_authority: &dyn Signer,
+    max_data_len: usize,
+) -> Result<Signature> {
+    let lamports = rpc_client
+        .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_program())
+        .await?;
+
+    #[allow(deprecated)]
+    let instructions = loader_instruction::deploy_with_max_program_len(
+        &fee_payer.pubkey(),
+        &program.pubkey(),
+        buffer,
+        &upgrade_authority.pubkey(),
+        lamports,
+        max_data_len,
+    )?;
+
+    println!(
+        "Deploying program {} from buffer {buffer}",
+        program.pubkey()
+    );
+    send_and_confirm(
+        rpc_client,
+        &instructions,
+        &[fee_payer, program, upgrade_authority],
+        fee_payer,
+    )
+    .await
+}
+
+/// Check that `upgrade_authority` is the upgrade authority of the deployed program. Returns the
+/// size of the program data, to be extended if the new program is larger.
+pub(crate) async fn ensure_upgrade_authority(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    upgrade_authority: &Pubkey,
+) -> Result<usize> {
+    let programdata_address = solana_loader_v3_interface::get_program_data_address(program_id);
+    let Some(LoaderAccount::ProgramData {
+        upgrade_authority: current_authority,
+        data,
+        ..
+    }) = fetch_loader_account(rpc_client, &programdata_address).await?
+    else {
+        bail!("Program {program_id} is not an upgradeable program or has not been deployed");
+    };
+
+    if current_authority != Some(*upgrade_authority) {
+        bail!(
+            "Program {program_id} upgrade authority is {}, not {upgrade_authority}",
+            current_authority.map_or_else(|| "none (immutable)".to_owned(), |a| a.to_string())
+        );
+    }
+
+    Ok(data.len())
+}
+
+/// Build the instructions upgrading `program_id` with the contents of `buffer`, extending the
+/// program data account first if the new program does not fit. Rent released by the buffer is
+/// sent to `spill`.
+pub(crate) async fn upgrade_instructions(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    upgrade_authority: &Pubkey,
+    payer: &Pubkey,
+    spill: &Pubkey,
+    program_len: usize,
+) -> Result<Vec<Instruction>> {
+    let data_len = ensure_upgrade_authority(rpc_client, program_id, upgrade_authority).await?;
+
+    let mut instructions = Vec::new();
+    if let Some(additional_bytes) = program_len.checked_sub(data_len).filter(|b| *b > 0) {
+        println!("Extending program data account by {additional_bytes} bytes");
+        instructions.push(loader_instruction::extend_program_checked(
+            program_id,
+            upgrade_authority,
+            Some(payer),
+            u32::try_from(additional_bytes)?,
+        ));
+    }
+
+    instructions.push(loader_instruction::upgrade(
+        program_id,
+        buffer,
+        upgrade_authority,
+        spill,
+    ));
+
+    Ok(instructions)
+}
+
+/// A buffer account found on-chain, with the lamports it holds.
+#[derive(Debug, Clone)]
+pub(crate) struct BufferAccount {
+    pub(crate) address: Pubkey,
+    pub(crate) authority: Pubkey,
+    pub(crate) lamports: u64,
+}
+
+/// Find the buffer accounts whose authority is `authority`. Only the buffer metadata is fetched.
+pub(crate) async fn find_buffers(
+    rpc_client: &RpcClient,
+    authority: &Pubkey,
+) -> Result<Vec<BufferAccount>> {
+    // Bincode layout of `UpgradeableLoaderState::Buffer { authority_address: Some(_) }`: a u32
+    // variant index followed by the option tag and the authority.
+    let mut buffer_prefix = 1_u32.to_le_bytes().to_vec();
+    buffer_prefix.push(1);
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, buffer_prefix)),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(5, authority.to_bytes().to_vec())),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig {
+                offset: 0,
+                length: UpgradeableLoaderState::size_of_buffer_metadata(),
+            }),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = rpc_client
+        .get_program_accounts_with_config(&solana_sdk_ids::bpf_loader_upgradeable::ID, config)
+        .await?;
+
+    Ok(accounts
+        .into_iter()
+        .map(|(address, account)| BufferAccount {
+            address,
+            authority: *authority,
+            lamports: account.lamports,
+        })
+        .collect())
+}
+
+/// Close a buffer, sending its lamports to `recipient`.
+pub(crate) fn close_buffer_instruction(buffer: &BufferAccount, recipient: &Pubkey) -> Instruction {
+    loader_instruction::close(&buffer.address, recipient, &buffer.authority)
+}
+
+/// Hand the buffer over from the key that wrote it to `new_authority`.
+pub(crate) async fn set_buffer_authority(
+    rpc_client: &RpcClient,
+    fee_payer: &dyn Signer,
+    buffer: &Pubkey,
+    current_authority: &dyn Signer,
+    new_authority: &Pubkey,
+) -> Result<Signature> {
+    let instruction = loader_instruction::set_buffer_authority(
+        buffer,
+        &current_authority.pubkey(),
+        new_authority,
+    );
+
+    println!("Setting buffer {buffer} authority to {new_authority}");
+    send_and_confirm(
+        rpc_client,
+        &[instruction],
+        &[fee_payer, current_authority],
+        fee_payer,
+    )
+    .await
+}
+
+fn sign_transaction(
+    instructions: &[Instruction],
+    signers: &[&dyn Signer],
+    fee_payer: &dyn Signer,
+    blockhash: Hash,
+) -> Result<Transaction> {
+    let message = Message::new_with_blockhash(instructions, Some(&fee_payer.pubkey()), &blockhash);
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction
+        .try_sign(&unique_signers(signers), blockhash)
+        .map_err(|e| eyre!("Failed to sign transaction: {e}"))?;
+
+    Ok(transaction)
+}
+
+/// Sign and send a transaction, waiting for confirmation.
+pub(crate) async fn send_and_confirm(
+    rpc_client: &RpcClient,
+    instructions: &[Instruction],
+    signers: &[&dyn Signer],
+    fee_payer: &dyn Signer,
+) -> Result<Signature> {
+    let blockhash = rpc_client.get_latest_blockhash().await?;
+    let transaction = sign_transaction(instructions, signers, fee_payer, blockhash)?;
+
+    Ok(rpc_client
+        .send_and_confirm_transaction_with_spinner(&transaction)
+        .await?)
+}
+
+/// The same key may be passed in several roles (e.g. fee payer and upgrade authority).
+fn unique_signers<'a>(signers: &[&'a dyn Signer]) -> Vec<&'a dyn Signer> {
+    let mut unique: Vec<&dyn Signer> = Vec::with_capacity(signers.len());
+    for signer in signers {
+        if !unique.iter().any(|s| s.pubkey() == signer.pubkey()) {
+            unique.push(*signer);
+        }
+    }
+    unique
+}
+
+/// Format an amount of lamports as SOL.
+#[allow(clippy::float_arithmetic, clippy::cast_precision_loss)]
+pub(crate) fn format_sol(lamports: u64) -> String {
+    format!("{:.9}", lamports as f64 / 1_000_000_000.0)
+}
+
+/// Single-line progress display for long running operations.
+struct Progress {
+    label: &'static str,
+    done: usize,
+    total: usize,
+}
+
+impl Progress {
+    fn new(label: &'static str, total: usize) -> Self {
+        let progress = Self {
+            label,
+            done: 0,
+            total,
+        };
+        progress.render();
+        progress
+    }
+
+    fn advance(&mut self, by: usize) {
+        self.done = self.done.saturating_add(by).min(self.total);
+        self.render();
+    }
+
+    /// Print a message on its own line without losing the progress line.
+    fn note(&self, message: &str) {
+        println!("\r{message}");
+        self.render();
+    }
+
+    fn render(&self) {
+        print!("\r{}: {}/{} chunks", self.label, self.done, self.total);
+        let _: std::io::Result<()> = std::io::stdout().flush();
+    }
+
+    fn finish(&self) {
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 64-bit ELF whose section header table starts at `section_headers_offset`, ending in the
+    /// zeros of its last section header like real binaries do.
+    fn elf(section_headers_offset: usize, section_header_count: u16) -> Vec<u8> {
+        let mut data = vec![0xaa_u8; section_headers_offset];
+        data[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        data[0x28..0x30].copy_from_slice(&(section_headers_offset as u64).to_le_bytes());
+        data[0x3a..0x3c].copy_from_slice(&64_u16.to_le_bytes());
+        data[0x3c..0x3e].copy_from_slice(&section_header_count.to_le_bytes());
+        for _ in 0..section_header_count {
+            let mut header = [0x11_u8; 64];
+            header[56..].fill(0);
+            data.extend_from_slice(&header);
+        }
+        data
+    }
+
+    #[test]
+    fn test_deployed_binary_keeps_trailing_zeros() {
+        let binary = elf(128, 3);
+        assert_eq!(binary.last(), Some(&0));
+
+        let mut program_data = binary.clone();
+        program_data.resize(binary.len() * 2, 0);
+
+        let deployed = deployed_binary(&program_data);
+        assert_eq!(deployed, binary.as_slice());
+        assert_eq!(
+            crate::artifact::sha256_hex(deployed),
+            crate::artifact::sha256_hex(&binary)
+        );
+        assert_ne!(strip_padding(&program_data), binary.as_slice());
+    }
+
+    #[test]
+    fn test_deployed_binary_without_elf_header() {
+        assert_eq!(deployed_binary(&[1, 2, 3, 0, 0]), &[1, 2, 3]);
+        assert_eq!(deployed_binary(&[]), &[] as &[u8]);
+
+        // A section header table past the end of the data falls back to stripping the padding.
+        let mut truncated = elf(128, 3);
+        truncated.truncate(200);
+        assert_eq!(deployed_binary(&truncated), strip_padding(&truncated));
+    }
+}
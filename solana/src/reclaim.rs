@@ -0,0 +1,337 @@
+//! Recovery of rent locked in accounts left behind by deployments and by the gateway: program
+//! buffers of interrupted deploys, and verifier set trackers, signature verification sessions and
+//! incoming messages of the gateway.
+
+use std::collections::BTreeSet;
+
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use clap::{Parser, Subcommand, ValueEnum};
+use eyre::eyre;
+use serde_json::Value;
+use solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+use crate::config::Config;
+use crate::deploy::{
+    confirm_action, load_fee_payer, load_signer, print_fee_payer_info, program_key_from_program,
+};
+use crate::program_loader::{self, BufferAccount, format_sol};
+use crate::types::Programs;
+use crate::utils::{
+    GATEWAY_KEY, get_program_upgrade_authority, read_json_file_from_path,
+    try_infer_program_id_from_env,
+};
+
+/// Number of buffers closed per transaction.
+const CLOSE_BUFFERS_PER_TRANSACTION: usize = 8;
+
+/// Offset of the status in an `IncomingMessage` account: it follows the discriminator, the bump
+/// and the signing PDA bump.
+const INCOMING_MESSAGE_STATUS_OFFSET: usize = 10;
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Commands {
+    /// List the program buffers owned by the upgrade authorities of the chain and the rent they
+    /// hold
+    Buffers(BuffersArgs),
+
+    /// Close program buffers and send their rent to a recipient
+    CloseBuffers(CloseBuffersArgs),
+
+    /// Report the rent held by the gateway PDAs of the chain
+    Pdas,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct BuffersArgs {
+    /// Additional buffer authorities to look for. The upgrade authorities in the chains info file
+    /// and the default Solana CLI keypair are always included.
+    #[clap(long)]
+    authority: Vec<Pubkey>,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct CloseBuffersArgs {
+    /// Buffer authority signer: keypair path or usb://ledger
+    #[clap(long)]
+    authority: String,
+
+    /// Buffers to close. Defaults to all buffers owned by the authority.
+    #[clap(long)]
+    buffer: Vec<Pubkey>,
+
+    /// Account receiving the buffers' lamports. Defaults to the authority.
+    #[clap(long)]
+    recipient: Option<Pubkey>,
+
+    /// Fee payer signer: keypair path or usb://ledger. Defaults to the Solana CLI keypair.
+    #[clap(long)]
+    fee_payer: Option<String>,
+
+    /// Skip confirmation prompt
+    #[clap(short = 'y', long)]
+    yes: bool,
+}
+
+pub(crate) async fn handle_command(command: Commands, config: &Config) -> eyre::Result<()> {
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+
+    match command {
+        Commands::Buffers(args) => list_buffers(&rpc_client, args, config).await,
+        Commands::CloseBuffers(args) => close_buffers(&rpc_client, args).await,
+        Commands::Pdas => report_pdas(&rpc_client, config).await,
+    }
+}
+
+async fn list_buffers(
+    rpc_client: &RpcClient,
+    args: BuffersArgs,
+    config: &Config,
+) -> eyre::Result<()> {
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let mut authorities: BTreeSet<Pubkey> = args.authority.into_iter().collect();
+    authorities.extend(Programs::value_variants().iter().filter_map(|program| {
+        get_program_upgrade_authority(&env, &config.chain, program_key_from_program(program))
+    }));
+    if let Ok(default_signer) = load_fee_payer(None) {
+        authorities.insert(default_signer.pubkey());
+    }
+
+    let mut total_buffers = 0_usize;
+    let mut total_lamports = 0_u64;
+
+    for authority in &authorities {
+        let buffers = program_loader::find_buffers(rpc_client, authority).await?;
+        if buffers.is_empty() {
+            continue;
+        }
+
+        println!("Authority {authority}:");
+        for buffer in &buffers {
+            println!(
+                "   {} ({} SOL)",
+                buffer.address,
+                format_sol(buffer.lamports)
+            );
+            total_lamports = total_lamports.saturating_add(buffer.lamports);
+        }
+        total_buffers = total_buffers.saturating_add(buffers.len());
+    }
+
+    println!("------------------------------------------");
+    println!(
+        "{total_buffers} buffer(s) holding {} SOL across {} authorities",
+        format_sol(total_lamports),
+        authorities.len()
+    );
+    if total_buffers > 0 {
+        println!("Close them with: reclaim close-buffers --authority <SIGNER>");
+    }
+
+    Ok(())
+}
+
+async fn close_buffers(rpc_client: &RpcClient, args: CloseBuffersArgs) -> eyre::Result<()> {
+    let authority = load_signer(&args.authority, "authority")?;
+    let fee_payer = load_fee_payer(args.fee_payer.as_deref())?;
+    let recipient = args.recipient.unwrap_or_else(|| authority.pubkey());
+
+    let owned = program_loader::find_buffers(rpc_client, &authority.pubkey()).await?;
+    let buffers: Vec<BufferAccount> = if args.buffer.is_empty() {
+        owned
+    } else {
+        args.buffer
+            .iter()
+            .map(|address| {
+                owned
+                    .iter()
+                    .find(|buffer| buffer.address == *address)
+                    .cloned()
+                    .ok_or_else(|| {
+                        eyre!("{address} is not a buffer owned by {}", authority.pubkey())
+                    })
+            })
+            .collect::<eyre::Result<_>>()?
+    };
+
+    if buffers.is_empty() {
+        println!("No buffers owned by {}", authority.pubkey());
+        return Ok(());
+    }
+
+    let total_lamports = buffers
+        .iter()
+        .fold(0_u64, |total, buffer| total.saturating_add(buffer.lamports));
+
+    print_fee_payer_info(rpc_client, &fee_payer.pubkey()).await;
+    println!("Authority: {}", authority.pubkey());
+    println!("Recipient: {recipient}");
+    for buffer in &buffers {
+        println!(
+            "   {} ({} SOL)",
+            buffer.address,
+            format_sol(buffer.lamports)
+        );
+    }
+    println!(
+        "Closing {} buffer(s) holding {} SOL",
+        buffers.len(),
+        format_sol(total_lamports)
+    );
+
+    if !args.yes && !confirm_action()? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    for chunk in buffers.chunks(CLOSE_BUFFERS_PER_TRANSACTION) {
+        let instructions: Vec<_> = chunk
+            .iter()
+            .map(|buffer| program_loader::close_buffer_instruction(buffer, &recipient))
+            .collect();
+        let signature = program_loader::send_and_confirm(
+            rpc_client,
+            &instructions,
+            &[fee_payer.as_ref(), authority.as_ref()],
+            fee_payer.as_ref(),
+        )
+        .await?;
+        println!("Closed {} buffer(s): {signature}", chunk.len());
+    }
+
+    println!("------------------------------------------");
+    println!(
+        "\u{2705} Reclaimed {} SOL to {recipient}",
+        format_sol(total_lamports)
+    );
+
+    Ok(())
+}
+
+/// Fetch the accounts of `program_id` of the Anchor account type `T`. With a `data_slice` only
+/// that part of the account data is downloaded; an empty slice is enough when only the lamports
+/// are of interest.
+async fn fetch_anchor_accounts<T: Discriminator>(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    data_slice: Option<UiDataSliceConfig>,
+) -> eyre::Result<Vec<(Pubkey, solana_sdk::account::Account)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            0,
+            T::DISCRIMINATOR.to_vec(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice,
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    Ok(rpc_client
+        .get_program_accounts_with_config(program_id, config)
+        .await?)
+}
+
+fn total_lamports(accounts: &[(Pubkey, solana_sdk::account::Account)]) -> u64 {
+    accounts.iter().fold(0_u64, |total, (_, account)| {
+        total.saturating_add(account.lamports)
+    })
+}
+
+async fn report_pdas(rpc_client: &RpcClient, config: &Config) -> eyre::Result<()> {
+    use solana_axelar_gateway::state::config::GatewayConfig;
+    use solana_axelar_gateway::state::verifier_set_tracker::VerifierSetTracker;
+    use solana_axelar_gateway::{IncomingMessage, MessageStatus, SignatureVerificationSessionData};
+
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    let gateway_id = try_infer_program_id_from_env(&env, &config.chain, GATEWAY_KEY)?;
+
+    let (gateway_config_pda, _) = GatewayConfig::find_pda();
+    let gateway_config_data = rpc_client.get_account_data(&gateway_config_pda).await?;
+    let gateway_config = GatewayConfig::try_deserialize(&mut gateway_config_data.as_slice())?;
+
+    println!("Gateway: {gateway_id}");
+    println!("Current epoch: {}", gateway_config.current_epoch);
+
+    let trackers =
+        fetch_anchor_accounts::<VerifierSetTracker>(rpc_client, &gateway_id, None).await?;
+    let mut superseded = 0_usize;
+    let mut superseded_lamports = 0_u64;
+    for (_, account) in &trackers {
+        let tracker = VerifierSetTracker::try_deserialize(&mut account.data.as_slice())?;
+        if tracker.epoch < gateway_config.current_epoch {
+            superseded = superseded.saturating_add(1);
+            superseded_lamports = superseded_lamports.saturating_add(account.lamports);
+        }
+    }
+
+    let sessions = fetch_anchor_accounts::<SignatureVerificationSessionData>(
+        rpc_client,
+        &gateway_id,
+        Some(UiDataSliceConfig {
+            offset: 0,
+            length: 0,
+        }),
+    )
+    .await?;
+
+    // There can be a lot of messages: only their status byte is downloaded.
+    let messages = fetch_anchor_accounts::<IncomingMessage>(
+        rpc_client,
+        &gateway_id,
+        Some(UiDataSliceConfig {
+            offset: INCOMING_MESSAGE_STATUS_OFFSET,
+            length: 1,
+        }),
+    )
+    .await?;
+    let mut executed = 0_usize;
+    let mut executed_lamports = 0_u64;
+    for (_, account) in &messages {
+        let status = MessageStatus::try_from_slice(&account.data)
+            .map_err(|_| eyre!("Failed to deserialize message status"))?;
+        if !status.is_approved() {
+            executed = executed.saturating_add(1);
+            executed_lamports = executed_lamports.saturating_add(account.lamports);
+        }
+    }
+
+    println!("------------------------------------------");
+    println!(
+        "Verifier set trackers: {} ({} SOL)",
+        trackers.len(),
+        format_sol(total_lamports(&trackers))
+    );
+    println!(
+        "   superseded by a later rotation: {superseded} ({} SOL)",
+        format_sol(superseded_lamports)
+    );
+    println!(
+        "Signature verification sessions: {} ({} SOL)",
+        sessions.len(),
+        format_sol(total_lamports(&sessions))
+    );
+    println!(
+        "Incoming messages: {} ({} SOL)",
+        messages.len(),
+        format_sol(total_lamports(&messages))
+    );
+    println!(
+        "   executed: {executed} ({} SOL)",
+        format_sol(executed_lamports)
+    );
+    println!("------------------------------------------");
+    println!(
+        "The gateway does not provide instructions to close these accounts, so their rent cannot be reclaimed yet."
+    );
+
+    Ok(())
+}
@@ -0,0 +1,515 @@
+//! Continuous relaying of MultisigProver proofs to the gateway: completed proofs are picked up as
+//! they appear and submitted as a signature verification session followed by the signer rotation
+//! or the message approvals, resuming from a persisted cursor after a restart.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_axelar_std::execute_data::{ExecuteData, MerklizedPayload};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::config::Config;
+use crate::deploy::{load_fee_payer, print_fee_payer_info};
+use crate::gateway;
+use crate::multisig_prover_types::ProofResponse;
+use crate::multisig_prover_types::msg::ProofStatus;
+use crate::program_loader;
+use crate::utils::{read_json_file_from_path, write_json_to_file_path};
+use crate::verification::{self, SignatureVerification};
+
+/// Delay between two attempts at sending the same transaction.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Bound on the widening of the lookahead window, as a multiple of `--lookahead`.
+const MAX_LOOKAHEAD_FACTOR: u64 = 64;
+
+#[derive(Parser, Debug)]
+pub(crate) struct RelayArgs {
+    /// First multisig session id to relay. Only used when there is no saved cursor to resume from.
+    #[clap(long, default_value_t = 1)]
+    start_session_id: u64,
+
+    /// Number of session ids past the cursor queried on each poll. Session ids are shared by all
+    /// the chains of the multisig contract, so the ones belonging to other chains are skipped. The
+    /// window doubles on each poll that finds no session of this chain, until one is found.
+    #[clap(long, default_value_t = 20)]
+    lookahead: u64,
+
+    /// Number of signature verification transactions sent at once
+    #[clap(long, default_value_t = verification::DEFAULT_VERIFICATION_PARALLELISM)]
+    verification_parallelism: usize,
+
+    /// Seconds to wait between two polls
+    #[clap(long, default_value_t = 10)]
+    poll_interval: u64,
+
+    /// Attempts at sending each transaction before giving up until the next poll
+    #[clap(long, default_value_t = 3)]
+    max_attempts: u32,
+
+    /// Read the proofs from `<session id>.json` files holding MultisigProver proof responses in
+    /// this directory instead of querying the MultisigProver. Meant for local testing.
+    #[clap(long)]
+    proofs_dir: Option<PathBuf>,
+
+    /// File persisting the relay cursor. Defaults to `relay-<chain>.json` in the output directory.
+    #[clap(long)]
+    state_file: Option<PathBuf>,
+
+    /// Fee payer signer: keypair path or usb://ledger. Defaults to the Solana CLI keypair.
+    #[clap(long)]
+    fee_payer: Option<String>,
+
+    /// Exit after the first poll that finds nothing to relay instead of polling forever
+    #[clap(long)]
+    once: bool,
+}
+
+/// Relay progress, saved after every proof so that a restarted relay neither skips nor resubmits
+/// anything. Progress within a proof is recovered from the verification session on chain.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct RelayState {
+    /// Every session id below this one is relayed or belongs to another chain.
+    next_session_id: u64,
+
+    /// Session ids past the cursor that are already relayed.
+    #[serde(default)]
+    relayed: BTreeSet<u64>,
+}
+
+impl RelayState {
+    /// Move the cursor over relayed sessions and over unknown sessions followed by a known one,
+    /// stopping at the first pending session. Returns whether the state changed.
+    fn advance_cursor(&mut self, unknown: &BTreeSet<u64>, highest_known: Option<u64>) -> bool {
+        let Some(highest_known) = highest_known else {
+            return false;
+        };
+
+        let first = self.next_session_id;
+        let mut next = first;
+        while next <= highest_known
+            && (self.relayed.remove(&next) || (unknown.contains(&next) && next < highest_known))
+        {
+            next = next.saturating_add(1);
+        }
+        self.next_session_id = next;
+        next != first
+    }
+}
+
+/// Where completed proofs are read from.
+enum ProofSource {
+    Prover { chains_info: Value, chain: String },
+    Directory(PathBuf),
+}
+
+impl ProofSource {
+    /// The proof of a session, or `None` when the session is unknown to the source (it belongs to
+    /// another chain or has not been created yet).
+    async fn fetch(&self, multisig_session_id: u64) -> eyre::Result<Option<ProofStatus>> {
+        match self {
+            Self::Prover { chains_info, chain } => {
+                match gateway::query_proof(chains_info, chain, multisig_session_id).await {
+                    Ok(response) => Ok(Some(response.status)),
+                    Err(error) if is_not_found(&error) => Ok(None),
+                    Err(error) => Err(error),
+                }
+            }
+            Self::Directory(dir) => {
+                let path = dir.join(format!("{multisig_session_id}.json"));
+                if !path.exists() {
+                    return Ok(None);
+                }
+                let response: ProofResponse = read_json_file_from_path(&path)?;
+                Ok(Some(response.status))
+            }
+        }
+    }
+}
+
+/// Whether the MultisigProver query failed because the contract has no proof for the session,
+/// which wasmd reports with the `NotFound` gRPC code.
+fn is_not_found(error: &eyre::Report) -> bool {
+    error
+        .downcast_ref::<tonic::Status>()
+        .is_some_and(|status| status.code() == tonic::Code::NotFound)
+}
+
+struct Relayer<'a> {
+    rpc_client: RpcClient,
+    verification_rpc_client: solana_client::rpc_client::RpcClient,
+    fee_payer: &'a dyn Signer,
+    max_attempts: u32,
+    verification_parallelism: usize,
+    state: RelayState,
+    state_file: PathBuf,
+    /// Number of session ids queried on the next poll.
+    window: u64,
+}
+
+pub(crate) async fn relay(args: RelayArgs, config: &Config) -> eyre::Result<()> {
+    let fee_payer = load_fee_payer(args.fee_payer.as_deref())?;
+    let source = match args.proofs_dir {
+        Some(dir) => ProofSource::Directory(dir),
+        None => ProofSource::Prover {
+            chains_info: read_json_file_from_path(&config.chains_info_file)?,
+            chain: config.chain.clone(),
+        },
+    };
+
+    let state_file = args.state_file.unwrap_or_else(|| {
+        config
+            .output_dir
+            .join(format!("relay-{}.json", config.chain))
+    });
+    let state = if state_file.exists() {
+        let state: RelayState = read_json_file_from_path(&state_file)?;
+        println!(
+            "Resuming from session {} ({})",
+            state.next_session_id,
+            state_file.display()
+        );
+        state
+    } else {
+        RelayState {
+            next_session_id: args.start_session_id,
+            ..RelayState::default()
+        }
+    };
+
+    let mut relayer = Relayer {
+        rpc_client: RpcClient::new_with_commitment(
+            config.url.clone(),
+            CommitmentConfig::confirmed(),
+        ),
+        verification_rpc_client: solana_client::rpc_client::RpcClient::new_with_commitment(
+            config.url.clone(),
+            CommitmentConfig::confirmed(),
+        ),
+        fee_payer: fee_payer.as_ref(),
+        max_attempts: args.max_attempts.max(1),
+        verification_parallelism: args.verification_parallelism,
+        state,
+        state_file,
+        window: args.lookahead.max(1),
+    };
+
+    print_fee_payer_info(&relayer.rpc_client, &fee_payer.pubkey()).await;
+
+    loop {
+        let window = relayer.window;
+        match relayer.poll(&source, args.lookahead).await {
+            // A poll that widened the window has not looked far enough to be conclusive.
+            Ok(0) if args.once && relayer.window <= window => break,
+            Ok(relayed) => {
+                if relayed > 0 {
+                    println!(
+                        "Relayed {relayed} proof(s), next session: {}",
+                        relayer.state.next_session_id
+                    );
+                }
+            }
+            Err(error) => eprintln!("Poll failed: {error:?}"),
+        }
+        tokio::time::sleep(Duration::from_secs(args.poll_interval)).await;
+    }
+
+    println!("Nothing left to relay");
+    Ok(())
+}
+
+impl Relayer<'_> {
+    fn save(&self) -> eyre::Result<()> {
+        write_json_to_file_path(&self.state, &self.state_file)
+    }
+
+    /// Relay the completed proofs found within the lookahead window of the cursor, in session
+    /// order, and move the cursor past the sessions that need nothing more. The window is widened
+    /// while none of its sessions is known, so that the cursor doesn't stall behind a run of
+    /// sessions of other chains longer than `lookahead`. Returns the number of proofs relayed.
+    async fn poll(&mut self, source: &ProofSource, lookahead: u64) -> eyre::Result<usize> {
+        let first = self.state.next_session_id;
+        let last = first.saturating_add(self.window);
+        let mut unknown = BTreeSet::new();
+        let mut highest_known = None;
+        let mut relayed = 0_usize;
+
+        for multisig_session_id in first..last {
+            if self.state.relayed.contains(&multisig_session_id) {
+                highest_known = Some(multisig_session_id);
+                continue;
+            }
+
+            match source.fetch(multisig_session_id).await? {
+                None => {
+                    unknown.insert(multisig_session_id);
+                }
+                Some(ProofStatus::Pending) => {
+                    println!("Session {multisig_session_id}: proof pending");
+                    highest_known = Some(multisig_session_id);
+                }
+                Some(ProofStatus::Completed { execute_data }) => {
+                    highest_known = Some(multisig_session_id);
+                    let result = self.relay_session(multisig_session_id, &execute_data).await;
+                    self.advance_cursor(&unknown, highest_known)?;
+                    result?;
+                    relayed = relayed.saturating_add(1);
+                }
+            }
+        }
+
+        self.advance_cursor(&unknown, highest_known)?;
+
+        if highest_known.is_some() {
+            self.window = lookahead.max(1);
+        } else {
+            let widest = lookahead.max(1).saturating_mul(MAX_LOOKAHEAD_FACTOR);
+            if self.window < widest {
+                self.window = self.window.saturating_mul(2).min(widest);
+                println!(
+                    "No session of this chain in {first}..{last}, looking {} sessions ahead",
+                    self.window
+                );
+            }
+        }
+        Ok(relayed)
+    }
+
+    fn advance_cursor(
+        &mut self,
+        unknown: &BTreeSet<u64>,
+        highest_known: Option<u64>,
+    ) -> eyre::Result<()> {
+        if self.state.advance_cursor(unknown, highest_known) {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    async fn relay_session(
+        &mut self,
+        multisig_session_id: u64,
+        execute_data: &[u8],
+    ) -> eyre::Result<()> {
+        let mut execute_data: ExecuteData = borsh::from_slice(execute_data)?;
+        if !self.skip_relayed_payload(&mut execute_data).await? {
+            println!("Session {multisig_session_id}: already on chain, skipping");
+            self.state.relayed.insert(multisig_session_id);
+            return self.save();
+        }
+        println!("Session {multisig_session_id}: relaying proof");
+
+        let gateway_config_pda = solana_axelar_gateway::GatewayConfig::find_pda().0;
+        let mut verification_flow = Vec::new();
+        let verification_session_pda = gateway::append_verification_flow_instructions(
+            &self.fee_payer.pubkey(),
+            &mut verification_flow,
+            &execute_data,
+            &gateway_config_pda,
+        )?;
+
+        let (initialize_session, signature_verifications) = verification_flow
+            .split_first()
+            .ok_or_else(|| eyre::eyre!("Empty verification flow"))?;
+        // The session may have been initialized by an earlier run that was interrupted.
+        if self.account_exists(&verification_session_pda).await? {
+            println!("   verification session {verification_session_pda} already initialized");
+        } else {
+            let signature = self.send(initialize_session).await?;
+            println!("   verification session {verification_session_pda}: {signature}");
+        }
+
+        let signature_verifications = signature_verifications
+            .iter()
+            .map(|instruction| {
+                SignatureVerification::parse(instruction)
+                    .ok_or_else(|| eyre::eyre!("Unexpected verification instruction"))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        // The verification waits on confirmations with the blocking client; keep it off the
+        // runtime's worker so that it doesn't stall other tasks.
+        tokio::task::block_in_place(|| {
+            verification::submit_signature_verifications(
+                &self.verification_rpc_client,
+                self.fee_payer,
+                &signature_verifications,
+                self.verification_parallelism,
+            )
+        })?;
+
+        let operator = self.rotation_operator(&execute_data).await?;
+        let mut payload_instructions = Vec::new();
+        gateway::append_payload_instructions(
+            &self.fee_payer.pubkey(),
+            operator,
+            &mut payload_instructions,
+            &execute_data,
+            &verification_session_pda,
+            &gateway_config_pda,
+        );
+        for instruction in &payload_instructions {
+            let signature = self.send(instruction).await?;
+            println!("   {signature}");
+        }
+
+        self.state.relayed.insert(multisig_session_id);
+        self.save()
+    }
+
+    /// Drop the messages whose `IncomingMessage` PDA already exists from the payload. Returns
+    /// false when nothing is left to submit: every message is approved, or the new verifier set
+    /// is already registered.
+    async fn skip_relayed_payload(&self, execute_data: &mut ExecuteData) -> eyre::Result<bool> {
+        match &mut execute_data.payload_items {
+            MerklizedPayload::VerifierSetRotation {
+                new_verifier_set_merkle_root,
+            } => {
+                let (tracker_pda, _) = solana_axelar_gateway::VerifierSetTracker::find_pda(
+                    new_verifier_set_merkle_root,
+                );
+                Ok(!self.account_exists(&tracker_pda).await?)
+            }
+            MerklizedPayload::NewMessages { messages } => {
+                let pdas: Vec<Pubkey> = messages
+                    .iter()
+                    .map(|message| {
+                        let command_id = gateway::command_id(
+                            &message.leaf.message.cc_id.chain,
+                            &message.leaf.message.cc_id.id,
+                        );
+                        solana_axelar_gateway::IncomingMessage::find_pda(&command_id).0
+                    })
+                    .collect();
+                let accounts = self.rpc_client.get_multiple_accounts(&pdas).await?;
+
+                let mut approved = accounts.iter().map(Option::is_some);
+                messages.retain(|message| {
+                    let is_approved = approved.next().unwrap_or(false);
+                    if is_approved {
+                        println!(
+                            "   message {} from {} already approved",
+                            message.leaf.message.cc_id.id, message.leaf.message.cc_id.chain
+                        );
+                    }
+                    !is_approved
+                });
+
+                Ok(!messages.is_empty())
+            }
+        }
+    }
+
+    /// The fee payer when the payload is a signer rotation and the fee payer is the gateway
+    /// operator, which rotates without waiting for the minimum rotation delay.
+    async fn rotation_operator(&self, execute_data: &ExecuteData) -> eyre::Result<Option<Pubkey>> {
+        use anchor_lang::AccountDeserialize;
+        use solana_axelar_gateway::state::config::GatewayConfig;
+
+        if !matches!(
+            execute_data.payload_items,
+            MerklizedPayload::VerifierSetRotation { .. }
+        ) {
+            return Ok(None);
+        }
+        let data = self
+            .rpc_client
+            .get_account_data(&GatewayConfig::find_pda().0)
+            .await?;
+        let gateway_config = GatewayConfig::try_deserialize(&mut data.as_slice())?;
+        let fee_payer = self.fee_payer.pubkey();
+
+        Ok((gateway_config.operator == fee_payer).then_some(fee_payer))
+    }
+
+    async fn account_exists(&self, address: &Pubkey) -> eyre::Result<bool> {
+        Ok(self
+            .rpc_client
+            .get_account_with_commitment(address, CommitmentConfig::confirmed())
+            .await?
+            .value
+            .is_some())
+    }
+
+    async fn send(&self, instruction: &Instruction) -> eyre::Result<Signature> {
+        let mut attempt = 1_u32;
+        loop {
+            match program_loader::send_and_confirm(
+                &self.rpc_client,
+                std::slice::from_ref(instruction),
+                &[self.fee_payer],
+                self.fee_payer,
+            )
+            .await
+            {
+                Ok(signature) => return Ok(signature),
+                Err(error) if attempt < self.max_attempts => {
+                    eprintln!("   attempt {attempt}/{} failed: {error}", self.max_attempts);
+                    attempt = attempt.saturating_add(1);
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(next_session_id: u64, relayed: &[u64]) -> RelayState {
+        RelayState {
+            next_session_id,
+            relayed: relayed.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn advance_cursor_skips_relayed_and_other_chains_sessions() {
+        let mut state = state(10, &[10, 12]);
+        let unknown = BTreeSet::from([11, 13]);
+
+        assert!(state.advance_cursor(&unknown, Some(14)));
+        assert_eq!(state.next_session_id, 14);
+        assert!(state.relayed.is_empty());
+    }
+
+    #[test]
+    fn advance_cursor_stops_at_pending_session() {
+        let mut state = state(10, &[10, 12]);
+        let unknown = BTreeSet::from([13]);
+
+        // 11 is pending: it is known but neither relayed nor unknown.
+        assert!(state.advance_cursor(&unknown, Some(14)));
+        assert_eq!(state.next_session_id, 11);
+        assert_eq!(state.relayed, BTreeSet::from([12]));
+    }
+
+    #[test]
+    fn advance_cursor_keeps_trailing_unknown_sessions() {
+        let mut state = state(10, &[10]);
+        let unknown = BTreeSet::from([11, 12]);
+
+        // Sessions past the highest known one may not have been created yet.
+        assert!(state.advance_cursor(&unknown, Some(10)));
+        assert_eq!(state.next_session_id, 11);
+    }
+
+    #[test]
+    fn advance_cursor_without_known_sessions() {
+        let mut state = state(10, &[]);
+        let unknown = BTreeSet::from([10, 11, 12]);
+
+        assert!(!state.advance_cursor(&unknown, None));
+        assert_eq!(state.next_session_id, 10);
+    }
+}
@@ -13,11 +13,15 @@ use crate::utils::{
     DEFAULT_COMPUTE_UNITS, DEFAULT_PRIORITY_FEE, create_compute_budget_instructions,
     print_transaction_result,
 };
+use crate::verification::{self, SignatureVerification};
+use crate::{gateway, program};
 
 #[derive(Debug)]
 pub(crate) struct SendArgs {
     pub(crate) fee_payer: Box<dyn Signer>,
     pub(crate) signers: Vec<String>,
+    /// Number of signature verification transactions sent at once
+    pub(crate) verification_parallelism: usize,
 }
 
 fn load_signers(
@@ -88,14 +92,10 @@ fn optimize_transaction(
     Ok(transaction)
 }
 
-fn add_compute_budget_to_transaction(
-    transaction: &Transaction,
-    signers: &[Box<dyn solana_sdk::signer::Signer>],
-    blockhash: &solana_sdk::hash::Hash,
-) -> eyre::Result<Transaction> {
-    let message = &transaction.message;
-
-    let original_instructions: Vec<solana_sdk::instruction::Instruction> = message
+fn decompile_instructions(
+    message: &solana_sdk::message::Message,
+) -> Vec<solana_sdk::instruction::Instruction> {
+    message
         .instructions
         .iter()
         .map(|compiled_ix| solana_sdk::instruction::Instruction {
@@ -114,7 +114,41 @@ fn add_compute_budget_to_transaction(
                 .collect(),
             data: compiled_ix.data.clone(),
         })
-        .collect();
+        .collect()
+}
+
+/// The signature verification performed by a transaction holding a single gateway
+/// `VerifySignature` instruction.
+fn signature_verification(transaction: &Transaction) -> Option<SignatureVerification> {
+    match decompile_instructions(&transaction.message).as_slice() {
+        [instruction] => SignatureVerification::parse(instruction),
+        _ => None,
+    }
+}
+
+/// The verification session of a transaction holding a single gateway
+/// `InitializePayloadVerificationSession` instruction, when that session already exists.
+fn existing_verification_session(
+    transaction: &Transaction,
+    rpc_client: &RpcClient,
+) -> eyre::Result<Option<solana_sdk::pubkey::Pubkey>> {
+    let session = match decompile_instructions(&transaction.message).as_slice() {
+        [instruction] => verification::initialized_session(instruction),
+        _ => None,
+    };
+
+    match session {
+        Some(session) if verification::session_exists(rpc_client, &session)? => Ok(Some(session)),
+        Some(_) | None => Ok(None),
+    }
+}
+
+fn add_compute_budget_to_transaction(
+    transaction: &Transaction,
+    signers: &[Box<dyn solana_sdk::signer::Signer>],
+    blockhash: &solana_sdk::hash::Hash,
+) -> eyre::Result<Transaction> {
+    let original_instructions = decompile_instructions(&transaction.message);
 
     let compute_budget_instructions =
         create_compute_budget_instructions(DEFAULT_COMPUTE_UNITS, DEFAULT_PRIORITY_FEE);
@@ -152,11 +186,44 @@ pub(crate) fn sign_and_send_transactions(
     let rpc_client = RpcClient::new_with_commitment(&config.url, CommitmentConfig::confirmed());
     let mut results = Vec::new();
 
-    let SendArgs { fee_payer, signers } = send_args;
+    let SendArgs {
+        fee_payer,
+        signers,
+        verification_parallelism,
+    } = send_args;
     let shared_payer: Rc<dyn Signer> = Rc::from(fee_payer);
 
-    for serializable_tx in serializable_txs {
-        let transaction = serializable_tx.transaction;
+    let mut serializable_txs = serializable_txs.into_iter().peekable();
+    while let Some(serializable_tx) = serializable_txs.next() {
+        let SerializableSolanaTransaction {
+            transaction,
+            params,
+        } = serializable_tx;
+
+        if let Some(session) = existing_verification_session(&transaction, &rpc_client)? {
+            println!("Verification session {session} already initialized, skipping");
+            continue;
+        }
+
+        // Consecutive signature verifications are sent concurrently, skipping the signatures
+        // already recorded in the session and stopping once it reaches quorum.
+        if let Some(first) = signature_verification(&transaction) {
+            let mut batch = vec![first];
+            while let Some(next) = serializable_txs
+                .peek()
+                .and_then(|next| signature_verification(&next.transaction))
+            {
+                batch.push(next);
+                serializable_txs.next();
+            }
+            results.extend(verification::submit_signature_verifications(
+                &rpc_client,
+                shared_payer.as_ref(),
+                &batch,
+                verification_parallelism,
+            )?);
+            continue;
+        }
         let mut signers = load_signers(&signers, &transaction)?;
         signers.push(Box::new(Rc::clone(&shared_payer)));
 
@@ -166,6 +233,17 @@ pub(crate) fn sign_and_send_transactions(
         match rpc_client.send_and_confirm_transaction(&optimized_tx) {
             Ok(signature) => {
                 results.push(signature);
+                program::apply_pending_update(config, &params)?;
+                if gateway::is_call_contract(&transaction) {
+                    // The transaction is sent: a failed message lookup must not fail the command.
+                    if let Err(err) =
+                        gateway::print_call_contract_messages(&rpc_client, config, &signature)
+                    {
+                        eprintln!(
+                            "\u{26A0}\u{FE0F}  Warning: failed to fetch the sent messages: {err}"
+                        );
+                    }
+                }
             }
             Err(err) => {
                 eprintln!("Error during transaction: {err}");
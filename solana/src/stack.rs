@@ -0,0 +1,594 @@
+//! Declarative deployment of the whole Axelar stack on Solana: program deployments followed by
+//! their initialization, executed in dependency order and resumable through a state file.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use eyre::eyre;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+use crate::InstructionSubcommand;
+use crate::artifact::{self, ArtifactArgs};
+use crate::config::Config;
+use crate::deploy::{self, DeployArgs, load_signer, program_key_from_program};
+use crate::program_loader::{self, LoaderAccount};
+use crate::send::{SendArgs, sign_and_send_transactions};
+use crate::types::Programs;
+use crate::utils::{
+    CHAINS_KEY, read_json_file_from_path, try_infer_program_id_from_env, write_json_to_file_path,
+};
+use crate::{gas_service, gateway, governance, its, memo, operators};
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Commands {
+    /// Deploy and initialize the programs described in a plan file, resuming from the state file
+    /// of a previous run
+    Deploy(StackDeployArgs),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct StackDeployArgs {
+    /// Path to the JSON deployment plan (see stack-plan.template.json)
+    #[clap(long)]
+    plan: PathBuf,
+
+    /// Path to the state file. Defaults to `stack-<chain>.state.json` in the output directory.
+    #[clap(long)]
+    state: Option<PathBuf>,
+
+    /// Ignore the state file and start from scratch (steps found on-chain are still skipped)
+    #[clap(long)]
+    restart: bool,
+
+    /// Resume from the state file even though the plan changed since it was written, keeping the
+    /// steps it records as completed
+    #[clap(long, conflicts_with = "restart")]
+    allow_plan_change: bool,
+
+    /// Skip confirmation prompt
+    #[clap(short = 'y', long)]
+    yes: bool,
+}
+
+/// Deployment plan, as read from the plan file.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Plan {
+    /// Version of the artifacts to deploy, unless overridden per program
+    version: Option<String>,
+    /// Upgrade authority signer: keypair path or usb://ledger
+    upgrade_authority: String,
+    /// Fee payer signer. Defaults to the upgrade authority, which programs require to pay for
+    /// their initialization.
+    fee_payer: Option<String>,
+    programs: Vec<PlannedProgram>,
+    gateway: Option<GatewayInit>,
+    operators: Option<OperatorsInit>,
+    gas_service: Option<GasServiceInit>,
+    governance: Option<GovernanceInit>,
+    memo: Option<MemoInit>,
+    its: Option<ItsInit>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct PlannedProgram {
+    program: Programs,
+    /// Signer for the program address: keypair path or usb://ledger
+    program_keypair: String,
+    version: Option<String>,
+    program_path: Option<String>,
+    artifact_dir: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct GatewayInit {
+    previous_signers_retention: u128,
+    minimum_rotation_delay: u64,
+    operator: String,
+    signer: Option<String>,
+    nonce: Option<u64>,
+    signer_set: Option<String>,
+    #[serde(default)]
+    signers: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct OperatorsInit {
+    owner: String,
+    #[serde(default)]
+    signers: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct GasServiceInit {
+    operator: String,
+    #[serde(default)]
+    signers: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct GovernanceInit {
+    governance_chain: String,
+    governance_address: String,
+    minimum_proposal_eta_delay: u32,
+    operator: String,
+    #[serde(default)]
+    signers: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct MemoInit {
+    #[serde(default)]
+    signers: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ItsInit {
+    operator: String,
+    /// Defaults to the chain the CLI runs against
+    chain_name: Option<String>,
+    its_hub_address: String,
+    /// Chains to trust once ITS is initialized, or `["all"]` for every chain in chains info
+    #[serde(default)]
+    trusted_chains: Vec<String>,
+    #[serde(default)]
+    signers: Vec<String>,
+}
+
+/// Progress of a stack deployment, persisted after every step.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct StackState {
+    /// SHA-256 hash of the plan the state belongs to
+    plan_hash: String,
+    completed: BTreeMap<String, String>,
+}
+
+impl StackState {
+    fn load(path: &Path) -> eyre::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        read_json_file_from_path(path).map(Some)
+    }
+
+    fn complete(&mut self, path: &Path, step: &str, detail: String) -> eyre::Result<()> {
+        self.completed.insert(step.to_owned(), detail);
+        write_json_to_file_path(self, path)
+    }
+}
+
+/// A single step of the plan.
+enum Step<'a> {
+    Deploy(&'a PlannedProgram),
+    Init(Programs, InstructionSubcommand, &'a [String]),
+    TrustChain(String),
+}
+
+impl Step<'_> {
+    fn id(&self) -> String {
+        match self {
+            Step::Deploy(planned) => format!("deploy-{}", program_name(&planned.program)),
+            Step::Init(program, ..) => format!("init-{}", program_name(program)),
+            Step::TrustChain(chain) => format!("its-trust-{chain}"),
+        }
+    }
+}
+
+enum Outcome {
+    Executed,
+    AlreadyOnChain,
+    FromState(String),
+}
+
+fn program_name(program: &Programs) -> String {
+    program
+        .to_possible_value()
+        .map(|v| v.get_name().to_owned())
+        .unwrap_or_default()
+}
+
+fn parse_pubkey(field: &str, value: &str) -> eyre::Result<Pubkey> {
+    Pubkey::from_str(value).map_err(|e| eyre!("Invalid {field} {value} in plan: {e}"))
+}
+
+pub(crate) async fn handle_command(command: Commands, config: &Config) -> eyre::Result<()> {
+    match command {
+        Commands::Deploy(args) => deploy_stack(args, config).await,
+    }
+}
+
+/// Expand the plan into its steps, in dependency order: every program is deployed before any is
+/// initialized, the gateway is initialized first and ITS last, followed by its trusted chains.
+fn plan_steps<'a>(plan: &'a Plan, config: &Config) -> eyre::Result<Vec<Step<'a>>> {
+    let deploy_order = [
+        Programs::Gateway,
+        Programs::Operators,
+        Programs::GasService,
+        Programs::Governance,
+        Programs::Memo,
+        Programs::Its,
+        Programs::Multicall,
+    ];
+    let mut steps: Vec<Step<'a>> = deploy_order
+        .iter()
+        .filter_map(|program| {
+            plan.programs
+                .iter()
+                .find(|planned| planned.program == *program)
+        })
+        .map(Step::Deploy)
+        .collect();
+
+    if let Some(init) = &plan.gateway {
+        let command = gateway::Commands::Init(gateway::InitArgs {
+            previous_signers_retention: init.previous_signers_retention,
+            minimum_rotation_delay: init.minimum_rotation_delay,
+            signer: init.signer.clone(),
+            nonce: init.nonce,
+            signer_set: init.signer_set.clone(),
+            operator: parse_pubkey("gateway.operator", &init.operator)?,
+        });
+        steps.push(Step::Init(
+            Programs::Gateway,
+            InstructionSubcommand::Gateway(command),
+            &init.signers,
+        ));
+    }
+
+    if let Some(init) = &plan.operators {
+        let command = operators::Commands::Init(operators::InitArgs {
+            owner: parse_pubkey("operators.owner", &init.owner)?,
+        });
+        steps.push(Step::Init(
+            Programs::Operators,
+            InstructionSubcommand::Operators(command),
+            &init.signers,
+        ));
+    }
+
+    if let Some(init) = &plan.gas_service {
+        let command = gas_service::Commands::Init(gas_service::InitArgs {
+            operator: parse_pubkey("gasService.operator", &init.operator)?,
+        });
+        steps.push(Step::Init(
+            Programs::GasService,
+            InstructionSubcommand::GasService(command),
+            &init.signers,
+        ));
+    }
+
+    if let Some(init) = &plan.governance {
+        let command = governance::Commands::Init(governance::InitArgs {
+            governance_chain: init.governance_chain.clone(),
+            governance_address: init.governance_address.clone(),
+            minimum_proposal_eta_delay: init.minimum_proposal_eta_delay,
+            operator: parse_pubkey("governance.operator", &init.operator)?,
+        });
+        steps.push(Step::Init(
+            Programs::Governance,
+            InstructionSubcommand::Governance(command),
+            &init.signers,
+        ));
+    }
+
+    if let Some(init) = &plan.memo {
+        steps.push(Step::Init(
+            Programs::Memo,
+            InstructionSubcommand::Memo(memo::Commands::Init),
+            &init.signers,
+        ));
+    }
+
+    if let Some(init) = &plan.its {
+        let command = its::Commands::Init(its::InitArgs {
+            operator: parse_pubkey("its.operator", &init.operator)?,
+            chain_name: init
+                .chain_name
+                .clone()
+                .unwrap_or_else(|| config.chain.clone()),
+            its_hub_address: init.its_hub_address.clone(),
+        });
+        steps.push(Step::Init(
+            Programs::Its,
+            InstructionSubcommand::Its(command),
+            &init.signers,
+        ));
+
+        if init.trusted_chains.iter().any(|chain| chain == "all") {
+            let chains_info: Value = read_json_file_from_path(&config.chains_info_file)?;
+            let chains = chains_info[CHAINS_KEY]
+                .as_object()
+                .ok_or_else(|| eyre!("Failed to load all chains from chains info JSON file"))?;
+            steps.extend(chains.keys().cloned().map(Step::TrustChain));
+        } else {
+            steps.extend(init.trusted_chains.iter().cloned().map(Step::TrustChain));
+        }
+    }
+
+    Ok(steps)
+}
+
+/// Account that exists once `program` has been initialized, or `None` for programs without an
+/// initialization step.
+fn initialized_account(program: &Programs) -> Option<Pubkey> {
+    match program {
+        Programs::Gateway => Some(solana_axelar_gateway::GatewayConfig::find_pda().0),
+        Programs::Operators => Some(solana_axelar_operators::OperatorRegistry::find_pda().0),
+        Programs::GasService => Some(
+            Pubkey::find_program_address(&[b"gas-service"], &solana_axelar_gas_service::id()).0,
+        ),
+        Programs::Governance => Some(solana_axelar_governance::GovernanceConfig::find_pda().0),
+        Programs::Memo => {
+            Some(Pubkey::find_program_address(&[b"counter"], &solana_axelar_memo::id()).0)
+        }
+        Programs::Its => Some(crate::its::find_its_root_pda().0),
+        Programs::Multicall => None,
+    }
+}
+
+/// Check whether a step has already been carried out on-chain.
+async fn done_on_chain(
+    step: &Step<'_>,
+    rpc_client: &RpcClient,
+    trusted_chains: &mut Option<Vec<String>>,
+    config: &Config,
+) -> eyre::Result<bool> {
+    match step {
+        Step::Deploy(planned) => {
+            let program_id = load_signer(&planned.program_keypair, "program-keypair")?.pubkey();
+            Ok(matches!(
+                program_loader::fetch_loader_account(rpc_client, &program_id).await?,
+                Some(LoaderAccount::Program { .. })
+            ))
+        }
+        Step::Init(program, ..) => {
+            let account = initialized_account(program)
+                .ok_or_else(|| eyre!("{} has no initialization step", program_name(program)))?;
+            Ok(rpc_client
+                .get_account_with_commitment(&account, rpc_client.commitment())
+                .await?
+                .value
+                .is_some())
+        }
+        Step::TrustChain(chain) => {
+            if trusted_chains.is_none() {
+                let blocking_client = solana_client::rpc_client::RpcClient::new(config.url.clone());
+                *trusted_chains = Some(crate::its::fetch_trusted_chains(&blocking_client)?);
+            }
+            Ok(trusted_chains
+                .as_ref()
+                .is_some_and(|trusted| trusted.contains(chain)))
+        }
+    }
+}
+
+async fn execute_step(step: Step<'_>, plan: &Plan, config: &Config) -> eyre::Result<String> {
+    let fee_payer_path = plan.fee_payer.as_ref().unwrap_or(&plan.upgrade_authority);
+
+    match step {
+        Step::Deploy(planned) => {
+            let mut artifact = ArtifactArgs {
+                program_path: None,
+                version: None,
+                artifact_dir: None,
+                offline: false,
+            };
+            match (
+                &planned.program_path,
+                &planned.artifact_dir,
+                planned.version.as_ref().or(plan.version.as_ref()),
+            ) {
+                (Some(path), _, _) => artifact.program_path = Some(path.clone()),
+                (None, Some(dir), _) => artifact.artifact_dir = Some(dir.clone()),
+                (None, None, Some(version)) => artifact.version = Some(version.clone()),
+                (None, None, None) => eyre::bail!(
+                    "No version, programPath or artifactDir given for {}",
+                    program_name(&planned.program)
+                ),
+            }
+
+            let args = DeployArgs {
+                program: planned.program.clone(),
+                program_keypair: planned.program_keypair.clone(),
+                upgrade_authority: plan.upgrade_authority.clone(),
+                fee_payer: Some(fee_payer_path.clone()),
+                buffer: None,
+                max_len: None,
+                yes: true,
+                artifact,
+            };
+            deploy::deploy_program(args, config.clone()).await?;
+
+            let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+            let program_id = try_infer_program_id_from_env(
+                &env,
+                &config.chain,
+                program_key_from_program(&planned.program),
+            )?;
+            Ok(format!("deployed at {program_id}"))
+        }
+        Step::Init(_, instruction, signers) => {
+            run_instruction(instruction, fee_payer_path, signers, config).await?;
+            Ok("initialized".to_owned())
+        }
+        Step::TrustChain(chain) => {
+            let signers = plan
+                .its
+                .as_ref()
+                .map(|its| its.signers.as_slice())
+                .unwrap_or_default();
+            let command = its::Commands::SetTrustedChain(its::TrustedChainArgs {
+                chain_name: chain,
+                authority: None,
+            });
+            run_instruction(
+                InstructionSubcommand::Its(command),
+                fee_payer_path,
+                signers,
+                config,
+            )
+            .await?;
+            Ok("trusted".to_owned())
+        }
+    }
+}
+
+async fn run_instruction(
+    instruction: InstructionSubcommand,
+    fee_payer_path: &str,
+    signers: &[String],
+    config: &Config,
+) -> eyre::Result<()> {
+    let fee_payer = load_signer(fee_payer_path, "fee-payer")?;
+    let transactions = crate::build_transaction(&fee_payer.pubkey(), instruction, config).await?;
+    sign_and_send_transactions(
+        SendArgs {
+            fee_payer,
+            signers: signers.to_vec(),
+            verification_parallelism: crate::verification::DEFAULT_VERIFICATION_PARALLELISM,
+        },
+        config,
+        transactions,
+    )
+}
+
+#[allow(clippy::too_many_lines)]
+async fn deploy_stack(args: StackDeployArgs, config: &Config) -> eyre::Result<()> {
+    let plan_bytes = std::fs::read(&args.plan)
+        .map_err(|e| eyre!("Failed to read plan {}: {e}", args.plan.display()))?;
+    let plan: Plan = serde_json::from_slice(&plan_bytes)
+        .map_err(|e| eyre!("Invalid plan {}: {e}", args.plan.display()))?;
+    let plan_hash = artifact::sha256_hex(&plan_bytes);
+
+    let state_path = args.state.clone().unwrap_or_else(|| {
+        config
+            .output_dir
+            .join(format!("stack-{}.state.json", config.chain))
+    });
+    let mut state = match StackState::load(&state_path)? {
+        Some(state) if !args.restart => {
+            if state.plan_hash != plan_hash {
+                if !args.allow_plan_change {
+                    eyre::bail!(
+                        "The plan changed since the state file {} was written. Pass --restart to \
+                         start over, or --allow-plan-change to keep its completed steps.",
+                        state_path.display()
+                    );
+                }
+                eprintln!(
+                    "WARNING: The plan changed since the state file {} was written. Completed steps are kept.",
+                    state_path.display()
+                );
+            }
+            println!(
+                "Resuming from {} ({} steps completed)",
+                state_path.display(),
+                state.completed.len()
+            );
+            state
+        }
+        _ => StackState::default(),
+    };
+    state.plan_hash = plan_hash;
+
+    let steps = plan_steps(&plan, config)?;
+    println!("------------------------------------------");
+    println!("Stack deployment plan ({} steps):", steps.len());
+    for (i, step) in steps.iter().enumerate() {
+        let id = step.id();
+        let mark = if state.completed.contains_key(&id) {
+            " (completed)"
+        } else {
+            ""
+        };
+        println!("   {}. {id}{mark}", i.saturating_add(1));
+    }
+    println!("------------------------------------------");
+
+    if !args.yes && !deploy::confirm_action()? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let rpc_client =
+        RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let mut trusted_chains = None;
+    let mut outcomes: Vec<(String, Outcome)> = Vec::with_capacity(steps.len());
+
+    for step in steps {
+        let id = step.id();
+        if let Some(detail) = state.completed.get(&id) {
+            outcomes.push((id, Outcome::FromState(detail.clone())));
+            continue;
+        }
+
+        println!("\n==> {id}");
+        if done_on_chain(&step, &rpc_client, &mut trusted_chains, config).await? {
+            println!("Already done on-chain, skipping.");
+            state.complete(&state_path, &id, "found on-chain".to_owned())?;
+            outcomes.push((id, Outcome::AlreadyOnChain));
+            continue;
+        }
+
+        match execute_step(step, &plan, config).await {
+            Ok(detail) => {
+                state.complete(&state_path, &id, detail)?;
+                outcomes.push((id, Outcome::Executed));
+            }
+            Err(err) => {
+                print_summary(&outcomes, Some(&id));
+                eprintln!(
+                    "\nStep {id} failed. Progress is saved in {}; re-run the same command to resume.",
+                    state_path.display()
+                );
+                return Err(err);
+            }
+        }
+    }
+
+    print_summary(&outcomes, None);
+
+    let env: Value = read_json_file_from_path(&config.chains_info_file)?;
+    println!("\nPrograms:");
+    for planned in &plan.programs {
+        let program_key = program_key_from_program(&planned.program);
+        let address = try_infer_program_id_from_env(&env, &config.chain, program_key)
+            .map_or_else(|_| "not recorded".to_owned(), |id| id.to_string());
+        println!("   {program_key}: {address}");
+    }
+
+    Ok(())
+}
+
+fn print_summary(outcomes: &[(String, Outcome)], failed: Option<&str>) {
+    println!("\n------------------------------------------");
+    println!("Stack deployment summary:");
+    for (id, outcome) in outcomes {
+        match outcome {
+            Outcome::Executed => println!("   \u{2705} {id}"),
+            Outcome::AlreadyOnChain => println!("   \u{23ed}\u{fe0f} {id} (already on-chain)"),
+            Outcome::FromState(detail) => {
+                println!("   \u{23ed}\u{fe0f} {id} (previous run: {detail})");
+            }
+        }
+    }
+    if let Some(id) = failed {
+        println!("   \u{274c} {id}");
+    }
+    println!("------------------------------------------");
+}
@@ -95,6 +95,27 @@ pub(crate) struct SolanaTransactionParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) nonce_authority: Option<String>,
     pub(crate) blockhash_for_message: String,
+    /// Chains info update to apply once the transaction has landed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pending_update: Option<PendingProgramUpdate>,
+}
+
+/// Chains info entry to update for a program once the transaction changing it has been broadcast.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct PendingProgramUpdate {
+    /// Key of the program in the chains info contracts (e.g. `AxelarGateway`)
+    pub(crate) program_key: String,
+    /// SHA-256 hash of the new program binary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) artifact_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) version: Option<String>,
+    /// New upgrade authority of the program
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) upgrade_authority: Option<String>,
+    /// Whether the program is made immutable
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) immutable: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -332,7 +353,8 @@ pub(crate) struct LocalSigner {
     pub(crate) weight: u128,
 }
 
-#[derive(Debug, Clone, clap::ValueEnum)]
+#[derive(Debug, Clone, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub(crate) enum Programs {
     Gateway,
     GasService,
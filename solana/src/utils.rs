@@ -43,6 +43,8 @@ static POSITIVE_DECIMAL_REGEX: std::sync::LazyLock<Regex> =
     std::sync::LazyLock::new(|| Regex::new(r"^\d*\.?\d+$").unwrap());
 
 pub(crate) const ADDRESS_KEY: &str = "address";
+pub(crate) const ARTIFACT_HASH_KEY: &str = "artifactHash";
+pub(crate) const ARTIFACT_VERSION_KEY: &str = "artifactVersion";
 pub(crate) const AXELAR_KEY: &str = "axelar";
 pub(crate) const CHAINS_KEY: &str = "chains";
 #[allow(dead_code)]
@@ -364,6 +366,19 @@ pub(crate) fn get_program_version(env: &Value, chain: &str, program_key: &str) -
     String::deserialize(v).ok()
 }
 
+/// Get the upgrade authority of a program from the chains info JSON file.
+/// Returns None if it is not set.
+pub(crate) fn get_program_upgrade_authority(
+    env: &Value,
+    chain: &str,
+    program_key: &str,
+) -> Option<Pubkey> {
+    let path =
+        format!("/{CHAINS_KEY}/{chain}/{CONTRACTS_KEY}/{program_key}/{UPGRADE_AUTHORITY_KEY}");
+    let v = env.pointer(&path)?;
+    Pubkey::from_str(v.as_str()?).ok()
+}
+
 /// Set the version of a program in the chains info JSON file.
 pub(crate) fn set_program_version(
     env: &mut Value,
@@ -388,6 +403,66 @@ pub(crate) fn set_program_version(
     Ok(())
 }
 
+/// Set the upgrade authority of a program in the chains info JSON file, `None` meaning the
+/// program is immutable.
+pub(crate) fn set_program_upgrade_authority(
+    env: &mut Value,
+    chain: &str,
+    program_key: &str,
+    upgrade_authority: Option<&str>,
+) -> eyre::Result<()> {
+    let contracts = env
+        .pointer_mut(&format!(
+            "/{CHAINS_KEY}/{chain}/{CONTRACTS_KEY}/{program_key}"
+        ))
+        .ok_or_else(|| {
+            eyre!("Could not find contract {program_key} for chain {chain} in chains info file")
+        })?;
+
+    contracts[UPGRADE_AUTHORITY_KEY] =
+        upgrade_authority.map_or(Value::Null, |a| Value::String(a.to_owned()));
+    Ok(())
+}
+
+/// Set the SHA-256 hash of the deployed program binary in the chains info JSON file.
+pub(crate) fn set_program_artifact_hash(
+    env: &mut Value,
+    chain: &str,
+    program_key: &str,
+    artifact_hash: &str,
+    artifact_version: Option<&str>,
+) -> eyre::Result<()> {
+    let contracts = env
+        .pointer_mut(&format!(
+            "/{CHAINS_KEY}/{chain}/{CONTRACTS_KEY}/{program_key}"
+        ))
+        .ok_or_else(|| {
+            eyre!("Could not find contract {program_key} for chain {chain} in chains info file")
+        })?;
+
+    set_artifact_hash_entry(contracts, artifact_hash, artifact_version);
+    Ok(())
+}
+
+/// Record the artifact hash in a contract entry, along with the version of the downloaded
+/// artifact it was computed from. Hashes of local builds have no artifact version, so that they
+/// are not mistaken for the hash of the released artifact of the entry's `version`.
+pub(crate) fn set_artifact_hash_entry(
+    contract: &mut Value,
+    artifact_hash: &str,
+    artifact_version: Option<&str>,
+) {
+    contract[ARTIFACT_HASH_KEY] = Value::String(artifact_hash.to_owned());
+    match artifact_version {
+        Some(version) => contract[ARTIFACT_VERSION_KEY] = Value::String(version.to_owned()),
+        None => {
+            if let Some(contract) = contract.as_object_mut() {
+                contract.remove(ARTIFACT_VERSION_KEY);
+            }
+        }
+    }
+}
+
 /// Ensure the contract entry exists in the chains info JSON, creating it if absent.
 pub(crate) fn ensure_contract_entry(env: &mut Value, chain: &str, program_key: &str) {
     let chains = env
@@ -0,0 +1,262 @@
+//! Submission of the signature verification transactions of a gateway proof. The transactions are
+//! sent in concurrent batches, the signatures already recorded in the verification session are
+//! skipped, and verification stops as soon as the session reaches quorum.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use eyre::eyre;
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+
+/// Number of signature verification transactions in flight at once.
+pub(crate) const DEFAULT_VERIFICATION_PARALLELISM: usize = 8;
+
+/// Attempts at verifying the same signature before giving up.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Index of the verification session PDA in the accounts of `InitializePayloadVerificationSession`
+/// and `VerifySignature`.
+const SESSION_ACCOUNT_INDEX: usize = 2;
+
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CONFIRMATION_POLLS: u32 = 120;
+
+/// A `VerifySignature` instruction and the signer it verifies.
+#[derive(Debug, Clone)]
+pub(crate) struct SignatureVerification {
+    pub(crate) instruction: Instruction,
+    session: Pubkey,
+    position: u16,
+    quorum: u128,
+}
+
+impl SignatureVerification {
+    /// Recognize a gateway `VerifySignature` instruction.
+    pub(crate) fn parse(instruction: &Instruction) -> Option<Self> {
+        use solana_axelar_gateway::instruction::VerifySignature;
+
+        if instruction.program_id != solana_axelar_gateway::id()
+            || !instruction.data.starts_with(VerifySignature::DISCRIMINATOR)
+        {
+            return None;
+        }
+        let args = VerifySignature::try_from_slice(
+            &instruction.data[VerifySignature::DISCRIMINATOR.len()..],
+        )
+        .ok()?;
+
+        Some(Self {
+            session: instruction.accounts.get(SESSION_ACCOUNT_INDEX)?.pubkey,
+            position: args.verifier_info.leaf.position,
+            quorum: args.verifier_info.leaf.quorum,
+            instruction: instruction.clone(),
+        })
+    }
+
+    pub(crate) const fn quorum(&self) -> u128 {
+        self.quorum
+    }
+}
+
+/// The verification session created by a gateway `InitializePayloadVerificationSession`
+/// instruction, if `instruction` is one.
+pub(crate) fn initialized_session(instruction: &Instruction) -> Option<Pubkey> {
+    use solana_axelar_gateway::instruction::InitializePayloadVerificationSession;
+
+    if instruction.program_id != solana_axelar_gateway::id()
+        || !instruction
+            .data
+            .starts_with(InitializePayloadVerificationSession::DISCRIMINATOR)
+    {
+        return None;
+    }
+
+    instruction
+        .accounts
+        .get(SESSION_ACCOUNT_INDEX)
+        .map(|account| account.pubkey)
+}
+
+/// The signatures recorded in a verification session.
+pub(crate) struct SessionProgress {
+    pub(crate) accumulated_threshold: u128,
+    signature_slots: [u8; 32],
+    pub(crate) signing_verifier_set_hash: [u8; 32],
+}
+
+impl SessionProgress {
+    /// Parse the data of a `SignatureVerificationSessionData` account.
+    pub(crate) fn parse(session: &Pubkey, mut data: &[u8]) -> eyre::Result<Self> {
+        let session_data =
+            solana_axelar_gateway::SignatureVerificationSessionData::try_deserialize(&mut data)
+                .map_err(|e| eyre!("Invalid verification session account {session}: {e}"))?;
+        let verification = session_data.signature_verification;
+
+        Ok(Self {
+            accumulated_threshold: verification.accumulated_threshold,
+            signature_slots: verification.signature_slots,
+            signing_verifier_set_hash: verification.signing_verifier_set_hash,
+        })
+    }
+
+    fn is_verified(&self, position: u16) -> bool {
+        let position = usize::from(position);
+        self.signature_slots
+            .get(position / 8)
+            .is_some_and(|byte| byte & (1 << (position % 8)) != 0)
+    }
+
+    /// Number of signatures verified in the session.
+    pub(crate) fn verified_signatures(&self) -> u32 {
+        self.signature_slots
+            .iter()
+            .map(|byte| byte.count_ones())
+            .sum()
+    }
+}
+
+/// Whether the verification session PDA already exists, e.g. because an earlier run initialized it.
+pub(crate) fn session_exists(rpc_client: &RpcClient, session: &Pubkey) -> eyre::Result<bool> {
+    Ok(rpc_client
+        .get_account_with_commitment(session, CommitmentConfig::confirmed())?
+        .value
+        .is_some())
+}
+
+fn fetch_session_progress(
+    rpc_client: &RpcClient,
+    session: &Pubkey,
+) -> eyre::Result<SessionProgress> {
+    SessionProgress::parse(session, &rpc_client.get_account_data(session)?)
+}
+
+/// Verify the signatures of a session until it reaches quorum, sending up to `parallelism`
+/// transactions at once. Signatures already recorded in the session are skipped, so an
+/// interrupted verification can simply be run again. Returns the signatures of the transactions
+/// sent.
+pub(crate) fn submit_signature_verifications(
+    rpc_client: &RpcClient,
+    fee_payer: &dyn Signer,
+    verifications: &[SignatureVerification],
+    parallelism: usize,
+) -> eyre::Result<Vec<Signature>> {
+    let Some(first) = verifications.first() else {
+        return Ok(Vec::new());
+    };
+    if verifications
+        .iter()
+        .any(|verification| verification.session != first.session)
+    {
+        eyre::bail!("Signature verifications belong to different sessions");
+    }
+
+    let mut attempts: HashMap<u16, u32> = HashMap::new();
+    let mut signatures = Vec::new();
+
+    loop {
+        let progress = fetch_session_progress(rpc_client, &first.session)?;
+        if progress.accumulated_threshold >= first.quorum {
+            println!(
+                "Quorum reached for session {} ({}/{})",
+                first.session, progress.accumulated_threshold, first.quorum
+            );
+            return Ok(signatures);
+        }
+
+        let pending: Vec<&SignatureVerification> = verifications
+            .iter()
+            .filter(|verification| !progress.is_verified(verification.position))
+            .collect();
+        if pending.is_empty() {
+            eyre::bail!(
+                "All signatures of session {} are verified but quorum is not reached ({}/{})",
+                first.session,
+                progress.accumulated_threshold,
+                first.quorum
+            );
+        }
+
+        let batch: Vec<&SignatureVerification> =
+            pending.into_iter().take(parallelism.max(1)).collect();
+        for verification in &batch {
+            let attempt = attempts.entry(verification.position).or_insert(0);
+            if *attempt >= MAX_ATTEMPTS {
+                eyre::bail!(
+                    "Failed to verify the signature of signer {} after {MAX_ATTEMPTS} attempts",
+                    verification.position
+                );
+            }
+            *attempt = attempt.saturating_add(1);
+        }
+
+        println!(
+            "Verifying {} signature(s) of session {} ({}/{} weight verified)",
+            batch.len(),
+            first.session,
+            progress.accumulated_threshold,
+            first.quorum
+        );
+        signatures.extend(send_batch(rpc_client, fee_payer, &batch)?);
+    }
+}
+
+/// Send the batch without waiting for each transaction, then wait for all of them. Returns the
+/// signatures of the confirmed transactions; failed ones are picked up by the next round.
+fn send_batch(
+    rpc_client: &RpcClient,
+    fee_payer: &dyn Signer,
+    batch: &[&SignatureVerification],
+) -> eyre::Result<Vec<Signature>> {
+    let blockhash = rpc_client.get_latest_blockhash()?;
+    let mut in_flight = Vec::with_capacity(batch.len());
+
+    for verification in batch {
+        let transaction = Transaction::new_signed_with_payer(
+            std::slice::from_ref(&verification.instruction),
+            Some(&fee_payer.pubkey()),
+            &[fee_payer],
+            blockhash,
+        );
+        match rpc_client.send_transaction(&transaction) {
+            Ok(signature) => in_flight.push(signature),
+            Err(err) => eprintln!("   signer {}: {err}", verification.position),
+        }
+    }
+
+    let mut confirmed = Vec::with_capacity(in_flight.len());
+    for _ in 0..CONFIRMATION_POLLS {
+        if in_flight.is_empty() {
+            break;
+        }
+        std::thread::sleep(CONFIRMATION_POLL_INTERVAL);
+
+        let statuses = rpc_client.get_signature_statuses(&in_flight)?.value;
+        let mut still_in_flight = Vec::with_capacity(in_flight.len());
+        for (signature, status) in in_flight.iter().zip(statuses) {
+            match status {
+                Some(status) if status.err.is_some() => {
+                    eprintln!("   {signature} failed: {:?}", status.err);
+                }
+                Some(status) if status.satisfies_commitment(CommitmentConfig::confirmed()) => {
+                    println!("   {signature}");
+                    confirmed.push(*signature);
+                }
+                Some(_) | None => still_in_flight.push(*signature),
+            }
+        }
+        in_flight = still_in_flight;
+    }
+
+    for signature in &in_flight {
+        eprintln!("   {signature} not confirmed in time");
+    }
+
+    Ok(confirmed)
+}